@@ -1,9 +1,10 @@
 from __future__ import annotations
 
-from typing import Iterator, Optional
+from typing import Iterator, Literal, Optional, overload
 
 class Document:
-    def __init__(self, url: str, doc: str): ...
+    def __init__(self, url: str, doc: str, record_locations: bool = False): ...
+    def base_url(self) -> str: ...
     def select(self, query: str) -> list[ElementRefView]: ...
 
 class ContextExtractor:
@@ -16,6 +17,35 @@ class ContextExtractor:
         only_keep_inline_tags: bool = True,
     ): ...
 
+class URLCanonicalization:
+    lowercase_host: bool
+    drop_fragment: bool
+    remove_tracking_params: bool
+    normalize_percent_encoding: bool
+    wikipedia_desktop: bool
+
+    def __init__(
+        self,
+        *,
+        lowercase_host: bool = False,
+        drop_fragment: bool = False,
+        remove_tracking_params: bool = False,
+        normalize_percent_encoding: bool = False,
+        wikipedia_desktop: bool = False,
+    ) -> None:
+        """Options to canonicalize urls after they are resolved, all options are disabled by default.
+        Urls are parsed when any option is enabled, which always removes the default port of the scheme (e.g., `:443` of https).
+
+        Arguments:
+            lowercase_host: lowercase the host
+            drop_fragment: remove the fragment (`#...`)
+            remove_tracking_params: remove tracking query parameters (utm_*, fbclid, gclid)
+            normalize_percent_encoding: decode percent-encoded unreserved characters and uppercase the remaining escapes
+            wikipedia_desktop: map mobile wikipedia hosts (e.g., en.m.wikipedia.org) to desktop hosts
+        """
+        ...
+    def canonicalize(self, url: str) -> str: ...
+
 class TableExtractor:
     def __init__(
         self,
@@ -25,7 +55,31 @@ class TableExtractor:
         discard_tags: Optional[list[str]] = None,
         only_keep_inline_tags: bool = True,
         html_error_forgiveness: bool = True,
-    ) -> None: ...
+        extract_nested_tables: bool = False,
+        html_spec_span: bool = False,
+        data_table_threshold: Optional[float] = None,
+        url_attrs: Optional[list[tuple[str, str]]] = None,
+        url_canonicalization: Optional[URLCanonicalization] = None,
+        table_id_scheme: Literal["index", "fingerprint"] = "index",
+        record_location: bool = False,
+        include_selector: Optional[str] = None,
+        exclude_selectors: list[str] = [],
+        extract_aria_tables: bool = False,
+    ) -> None:
+        """
+        Arguments:
+            table_id_scheme: how ids of tables are created, `index` uses the order of the table in the page (`url?table_no=N`),
+                `fingerprint` uses a hash of the structure of the table (`url?table_fp=H`) so that the id does not change when
+                unrelated tables are added to or removed from the page
+            record_location: whether to record the locations (css path and line in the source) of tables, rows and cells.
+                Documents passed to `extract_document` must be created with `record_locations=True` to have lines
+            include_selector: only extract tables matching this css selector (e.g., `table.wikitable`, `#content table`)
+            exclude_selectors: skip tables that match or are inside elements matching any of these css selectors (e.g., `.navbox`)
+            extract_aria_tables: whether to also extract tables built from other elements (e.g., div) with ARIA table roles
+                (table, grid, row, cell, columnheader, etc.) or `display: table` inline styles, spans are read from `aria-rowspan`
+                and `aria-colspan`
+        """
+        ...
     def extract(
         self,
         url: str,
@@ -34,11 +88,171 @@ class TableExtractor:
         auto_pad: bool = True,
         extract_context: bool = True,
     ) -> list[Table]: ...
+    def extract_document(
+        self,
+        doc: Document,
+        auto_span: bool = True,
+        auto_pad: bool = True,
+        extract_context: bool = True,
+    ) -> list[Table]:
+        """Extract tables from a parsed document. Lines and columns of the locations are only available
+        when the document is created with `record_locations=True`"""
+        ...
+    @overload
+    def extract_many(
+        self,
+        pages: list[tuple[str, str]],
+        auto_span: bool = True,
+        auto_pad: bool = True,
+        extract_context: bool = True,
+        num_threads: Optional[int] = None,
+        return_exceptions: Literal[False] = False,
+    ) -> list[list[Table]]:
+        """Extract tables from many pages (pairs of url and html) in parallel without holding the GIL.
+        Return the tables of each page in the same order as the pages, the error of the first page that
+        fails is raised"""
+        ...
+    @overload
+    def extract_many(
+        self,
+        pages: list[tuple[str, str]],
+        auto_span: bool = True,
+        auto_pad: bool = True,
+        extract_context: bool = True,
+        num_threads: Optional[int] = None,
+        *,
+        return_exceptions: Literal[True],
+    ) -> list[tuple[list[Table], Optional[Exception]]]:
+        """Same as above, but return a pair of (tables, error) for each page instead of raising errors,
+        the tables of a page that fails are empty"""
+        ...
+    def extract_with_diagnostics(
+        self,
+        url: str,
+        doc: str,
+        auto_span: bool = True,
+        auto_pad: bool = True,
+        extract_context: bool = True,
+    ) -> tuple[list[Table], list[DiscardedTable]]: ...
+
+class DiscardedTable:
+    @property
+    def table_index(self) -> int: ...
+    @property
+    def path(self) -> str: ...
+    @property
+    def reason(self) -> str: ...
+    @property
+    def span_position(self) -> Optional[tuple[int, int, int]]: ...
+    def to_dict(self) -> dict: ...
+
+class KeyValueExtractor:
+    def __init__(
+        self,
+        context_extractor: ContextExtractor,
+        *,
+        ignored_tags: Optional[list[str]] = None,
+        discard_tags: Optional[list[str]] = None,
+        keep_tags: Optional[list[str]] = None,
+        only_keep_inline_tags: bool = True,
+        url_attrs: Optional[list[tuple[str, str]]] = None,
+        url_canonicalization: Optional[URLCanonicalization] = None,
+    ) -> None:
+        """Extract key-value pairs from definition lists (dl/dt/dd) and infoboxes (tables of class `infobox`
+        or tables whose rows are pairs of a header cell and a data cell)."""
+        ...
+    def extract(
+        self, url: str, doc: str, extract_context: bool = True
+    ) -> list[KeyValueRecord]: ...
+
+class KeyValueRecord:
+    @property
+    def id(self) -> str: ...
+    @property
+    def url(self) -> str: ...
+    @property
+    def tag(self) -> Literal["dl", "table"]: ...
+    @property
+    def title(self) -> str: ...
+    @property
+    def attrs(self) -> dict[str, str]: ...
+    @property
+    def context(self) -> list[ContentHierarchy]: ...
+    @property
+    def pairs(self) -> list[KeyValuePair]: ...
+    def to_list(self) -> list[tuple[str, str]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(dat: str) -> KeyValueRecord: ...
+
+class KeyValuePair:
+    @property
+    def key(self) -> RichText: ...
+    @property
+    def value(self) -> RichText: ...
+
+class ListExtractor:
+    def __init__(
+        self,
+        context_extractor: ContextExtractor,
+        *,
+        ignored_tags: Optional[list[str]] = None,
+        discard_tags: Optional[list[str]] = None,
+        only_keep_inline_tags: bool = True,
+        min_items: int = 1,
+        url_attrs: Optional[list[tuple[str, str]]] = None,
+        url_canonicalization: Optional[URLCanonicalization] = None,
+    ) -> None:
+        """Extract standalone lists (ul/ol) and their nested lists. Lists inside tables or nav elements are skipped.
+
+        Arguments:
+            discard_tags: tags whose text is not extracted, ul and ol are always added as nested lists are
+                extracted as children of the items
+            min_items: lists with fewer items (not counting nested items) are skipped
+        """
+        ...
+    def extract(self, url: str, doc: str, extract_context: bool = True) -> list[List]: ...
+
+class List:
+    @property
+    def id(self) -> str: ...
+    @property
+    def url(self) -> str: ...
+    @property
+    def tag(self) -> Literal["ul", "ol"]: ...
+    @property
+    def attrs(self) -> dict[str, str]: ...
+    @property
+    def context(self) -> list[ContentHierarchy]: ...
+    @property
+    def items(self) -> list[ListItem]: ...
+    def flatten(self) -> list[ListItem]: ...
+    def to_list(self) -> list[tuple[int, str]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(dat: str) -> List: ...
+
+class ListItem:
+    @property
+    def value(self) -> RichText: ...
+    @property
+    def list_type(self) -> Literal["ul", "ol"]: ...
+    @property
+    def depth(self) -> int: ...
+    @property
+    def children(self) -> list[ListItem]: ...
 
 class Table:
+    """Methods running the native algorithms (e.g., `span`, `detect_header`, `to_markdown`, `to_csv`)
+    work on a deep copy of the table. The copy is cached until the table or one of its rows, cells or
+    content hierarchies is modified (rows and cells shared by several tables invalidate the copies of
+    all of them), or until any rich text is modified in place (e.g., `set_element_attr_by_id`), as rich
+    texts do not know the tables containing them."""
+
     id: str
     url: str
     caption: str
+    location: Optional[SourceLocation]
 
     def __init__(
         self,
@@ -48,6 +262,7 @@ class Table:
         attrs: dict[str, str],
         context: list[ContentHierarchy],
         rows: list[Row],
+        location: Optional[SourceLocation] = None,
     ) -> None: ...
     @property
     def attrs(self) -> dict[str, str]: ...
@@ -56,7 +271,17 @@ class Table:
     @property
     def rows(self) -> list[Row]: ...
     def span(self) -> Table: ...
+    def span_html_spec(self) -> Table: ...
     def pad(self) -> Optional[Table]: ...
+    def detect_header(self) -> tuple[int, int]: ...
+    def get_features(self) -> TableFeatures: ...
+    def get_data_table_score(self) -> float: ...
+    def get_column_header_paths(
+        self, n_header_rows: Optional[int] = None
+    ) -> list[list[str]]: ...
+    def get_column_names(
+        self, n_header_rows: Optional[int] = None, separator: str = " / "
+    ) -> list[str]: ...
     def n_rows(self) -> int: ...
     def shape(self) -> tuple[int, int]: ...
     def get_row(self, ri: int) -> Row: ...
@@ -75,9 +300,87 @@ class Table:
     def from_base64(dat: str) -> Table: ...
     def to_dict(self) -> dict: ...
     def to_list(self) -> list[list[str]]: ...
+    def to_csv(
+        self,
+        *,
+        delimiter: str = ",",
+        quoting: Literal["necessary", "always", "non_numeric", "never"] = "necessary",
+        header: bool = True,
+        n_header_rows: Optional[int] = None,
+        link_columns: bool = False,
+    ) -> str:
+        """Write the table to CSV (or TSV with `delimiter="\\t"`), the table is spanned if it is not.
+
+        Arguments:
+            header: whether to write the header rows as one row of column names (header rows are merged,
+                e.g., `Population / 2010`), otherwise, all rows are written as they are. Tables without header
+                rows are written with a row of empty column names
+            n_header_rows: number of header rows, detected automatically if not provided
+            link_columns: add a column containing links (separated by spaces) after each column having links
+        """
+        ...
+    @staticmethod
+    def from_csv(
+        dat: str,
+        *,
+        id: str = "",
+        url: str = "",
+        delimiter: str = ",",
+        quoting: Literal["necessary", "always", "non_numeric", "never"] = "necessary",
+        header: bool = True,
+        n_header_rows: Optional[int] = None,
+    ) -> Table:
+        """Read a table from CSV (or TSV), the first `n_header_rows` (default 1) rows are header rows if `header` is true,
+        except a first row of empty column names (written by `to_csv` for tables without header rows), which is skipped.
+        Fields are not unquoted if quoting is `never`."""
+        ...
+    def to_markdown(self, n_header_rows: Optional[int] = None) -> str:
+        """Render the table to a Markdown table, the table is spanned if it is not (values of merged cells are repeated)
+        and multiple header rows are merged into one. The number of header rows is detected if not provided."""
+        ...
+    def to_html(self) -> str:
+        """Render the table to a clean HTML table, elements in cells only keep meaningful attributes (e.g., href of
+        links, src and alt of images), merged cells of a spanned table are collapsed back into cells with rowspan
+        and colspan, rowspan and colspan of a not spanned table are kept."""
+        ...
+    def to_latex(self, n_header_rows: Optional[int] = None) -> str:
+        """Render the table to a LaTeX tabular (merged cells use `\\multicolumn` and `\\multirow`),
+        the number of header rows is detected if not provided."""
+        ...
+
+class TableFeatures:
+    @property
+    def is_presentation(self) -> bool: ...
+    @property
+    def has_header_markup(self) -> bool: ...
+    @property
+    def n_rows(self) -> int: ...
+    @property
+    def n_cols(self) -> int: ...
+    @property
+    def n_non_empty_cells(self) -> int: ...
+    @property
+    def avg_text_len(self) -> float: ...
+    @property
+    def block_content_ratio(self) -> float: ...
+    @property
+    def column_type_uniformity(self) -> float: ...
+    def score(self) -> float: ...
 
 class Row:
-    def __init__(self, cells: list[Cell], attrs: dict[str, str]) -> None: ...
+    section: str
+    # index of the row group element (thead, tbody, tfoot) containing the row, rowspan does not go past the group
+    row_group: int
+    location: Optional[SourceLocation]
+
+    def __init__(
+        self,
+        cells: list[Cell],
+        attrs: dict[str, str],
+        section: str = "tbody",
+        row_group: int = 0,
+        location: Optional[SourceLocation] = None,
+    ) -> None: ...
     @property
     def cells(self) -> list[Cell]: ...
     @property
@@ -92,12 +395,57 @@ class Cell:
     rowspan: int
     colspan: int
     value: RichText
+    nested_table_ids: list[str]
+    span_conflict: bool
+    origin: Optional[CellOrigin]
+    location: Optional[SourceLocation]
 
     def __init__(
-        self, is_header: bool, rowspan: int, colspan: int, value: RichText
+        self,
+        is_header: bool,
+        rowspan: int,
+        colspan: int,
+        attrs: dict[str, str],
+        value: RichText,
+        nested_table_ids: list[str] = [],
+        span_conflict: bool = False,
+        origin: Optional[CellOrigin] = None,
+        location: Optional[SourceLocation] = None,
     ) -> None: ...
     @property
     def attrs(self) -> dict[str, str]: ...
+    def is_span_copy(self, ri: int, ci: int) -> bool: ...
+    def to_dict(self) -> dict: ...
+
+class CellOrigin:
+    def __init__(
+        self, row: int, cell: int, top: int, left: int, bottom: int, right: int
+    ) -> None: ...
+    @property
+    def row(self) -> int: ...
+    @property
+    def cell(self) -> int: ...
+    @property
+    def top(self) -> int: ...
+    @property
+    def left(self) -> int: ...
+    @property
+    def bottom(self) -> int: ...
+    @property
+    def right(self) -> int: ...
+    def rowspan(self) -> int: ...
+    def colspan(self) -> int: ...
+
+class SourceLocation:
+    def __init__(
+        self, css_path: str, line: Optional[int] = None, column: Optional[int] = None
+    ) -> None: ...
+    @property
+    def css_path(self) -> str: ...
+    @property
+    def line(self) -> Optional[int]: ...
+    @property
+    def column(self) -> Optional[int]: ...
     def to_dict(self) -> dict: ...
 
 class ContentHierarchy:
@@ -142,12 +490,44 @@ class RichTextConfig:
         """
         ...
 
+# unit of offsets into the text of a rich text: bytes (UTF-8), characters (code points) or UTF-16 code units
+OffsetUnit = Literal["byte", "char", "utf16"]
+
 class RichText:
     @staticmethod
     def from_str(text: str) -> RichText: ...
     @property
     def text(self) -> str: ...
-    def len(self) -> int: ...
+    def len(self, unit: OffsetUnit = "byte") -> int: ...
+    def convert_offset(
+        self, offset: int, from_unit: OffsetUnit, to_unit: OffsetUnit
+    ) -> Optional[int]:
+        """Convert an offset into the text between units, return None if the offset is out of the text
+        or not at a character boundary"""
+        ...
+    def get_element_range(self, id: int, unit: OffsetUnit = "byte") -> tuple[int, int]:
+        """Get the range (start, end) of an element, e.g., use `char` to slice python strings
+        and `utf16` for javascript strings"""
+        ...
+    def get_element_ranges(self, unit: OffsetUnit = "byte") -> list[tuple[int, int]]: ...
+    def slice(self, start: int, end: int, unit: OffsetUnit = "byte") -> RichText:
+        """Get a part of the text between the offsets, elements outside of the range are removed
+        and the others are clipped. Empty elements (e.g., images) at the start of the range are only
+        kept when the range starts at the beginning of the text, so adjacent slices do not share them"""
+        ...
+    @staticmethod
+    def concat(texts: list[RichText], separator: str = "") -> RichText: ...
+    def replace_range(
+        self, start: int, end: int, replacement: str, unit: OffsetUnit = "byte"
+    ) -> RichText:
+        """Replace the text between the offsets, elements containing the range are resized
+        and elements after the range are shifted"""
+        ...
+    def substitute(self, pattern: str, replacement: str) -> RichText:
+        """Replace every match of the (rust) regex, capture groups are referred to as `$1` or `${name}`"""
+        ...
+    def strip(self) -> RichText: ...
+    def split(self, separator: str) -> list[RichText]: ...
     def iter_element_id(self) -> Iterator[int]: ...
     def iter_element_id_preorder(self) -> Iterator[int]: ...
     def get_element_tag_by_id(self, id: int) -> str: ...
@@ -155,6 +535,15 @@ class RichText:
     def set_element_attr_by_id(self, id: int, attr: str, value: str) -> None: ...
     def get_element_by_id(self, id: int) -> RichTextElement: ...
     def set_element_by_id(self, id: int, RichTextElement) -> None: ...
+    def to_html(
+        self, *, render_outer_element: bool = True, render_element_attrs: bool = False
+    ) -> str:
+        """Render the text to HTML, the text and attribute values are written as they are (not escaped)"""
+        ...
+    def to_markdown(self) -> str:
+        """Render links, images, emphasis, code, sup/sub, headings and lists (ul/ol/li, need to be kept
+        when extracting the rich text) to Markdown, special characters in the text are escaped"""
+        ...
     def to_dict(self) -> dict: ...
     @staticmethod
     def from_dict(c: dict) -> RichText: ...
@@ -163,9 +552,13 @@ class RichTextElement:
     @property
     def tag(self) -> str: ...
     @property
-    def start(self) -> int: ...
+    def start(self) -> int:
+        """start of the element in bytes, see `RichText.get_element_range` for other units"""
+        ...
     @property
-    def end(self) -> int: ...
+    def end(self) -> int:
+        """end of the element in bytes, see `RichText.get_element_range` for other units"""
+        ...
     @property
     def attrs(self) -> dict[str, str]: ...
     def get_attr(self, name: str) -> str: ...
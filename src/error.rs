@@ -1,8 +1,11 @@
 use postcard;
+#[cfg(feature = "python")]
 use pyo3::PyErr;
 use thiserror::Error;
 
+#[cfg(feature = "python")]
 pyo3::create_exception!(rsoup, OverlapSpanPyError, pyo3::exceptions::PyException);
+#[cfg(feature = "python")]
 pyo3::create_exception!(rsoup, InvalidCellSpanPyError, pyo3::exceptions::PyException);
 
 /// Represent possible errors returned by this library.
@@ -11,8 +14,11 @@ pub enum RSoupError {
     #[error("InvalidHTMLStructureError: '{0}'")]
     InvalidHTMLStructureError(&'static str),
 
-    #[error("OverlapSpanError: '{0}'")]
-    OverlapSpanError(String),
+    #[error("OverlapSpanError: cell {cell} of row {row} overlaps with a rowspan at column {col}")]
+    OverlapSpanError { row: usize, cell: usize, col: usize },
+
+    #[error("InvalidCellSpanError: colspan of cell {cell} of row {row} exceeds the number of columns at column {col}")]
+    InvalidCellSpanError { row: usize, cell: usize, col: usize },
 
     #[error("InvalidRowSpanError: '{0}'")]
     InvalidRowSpanError(String),
@@ -20,11 +26,18 @@ pub enum RSoupError {
     #[error("InvalidColSpanError: '{0}'")]
     InvalidColSpanError(String),
 
+    #[error("InvalidSelectorError: '{0}'")]
+    InvalidSelectorError(String),
+
+    #[error("UnsupportedFormatError: {0}")]
+    UnsupportedFormatError(String),
+
     /// Represents all other cases of `std::io::Error`.
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 
     /// PyO3 error
+    #[cfg(feature = "python")]
     #[error(transparent)]
     PyErr(#[from] pyo3::PyErr),
 
@@ -32,12 +45,18 @@ pub enum RSoupError {
     PostcardError(#[from] postcard::Error),
 }
 
+#[cfg(feature = "python")]
 pub fn into_pyerr<E: Into<RSoupError>>(err: E) -> PyErr {
     let hderr = err.into();
-    if let RSoupError::PyErr(e) = hderr {
-        e
-    } else {
-        let anyerror: anyhow::Error = hderr.into();
-        anyerror.into()
+    match hderr {
+        RSoupError::PyErr(e) => e,
+        RSoupError::OverlapSpanError { .. } => OverlapSpanPyError::new_err(hderr.to_string()),
+        RSoupError::InvalidCellSpanError { .. } => {
+            InvalidCellSpanPyError::new_err(hderr.to_string())
+        }
+        _ => {
+            let anyerror: anyhow::Error = hderr.into();
+            anyerror.into()
+        }
     }
 }
@@ -14,11 +14,10 @@ use crate::{
 use anyhow::Result;
 use ego_tree::NodeRef;
 use hashbrown::HashSet;
-use pyo3::prelude::*;
 use scraper::Node;
 
 #[derive(Clone)]
-#[pyclass(module = "rsoup.core")]
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
 pub struct ContextExtractor {
     // do not include those tags in the rich text
     ignored_tags: HashSet<String>,
@@ -31,18 +30,8 @@ pub struct ContextExtractor {
     only_keep_inline_tags: bool,
 }
 
-#[pymethods]
 impl ContextExtractor {
-    #[new]
-    #[args(
-        "*",
-        ignored_tags = "None",
-        discard_tags = "None",
-        same_content_level_elements = "None",
-        header_elements = "None",
-        only_keep_inline_tags = "true"
-    )]
-    fn new(
+    pub fn new(
         ignored_tags: Option<Vec<&str>>,
         discard_tags: Option<Vec<&str>>,
         same_content_level_elements: Option<Vec<&str>>,
@@ -82,9 +71,7 @@ impl ContextExtractor {
             only_keep_inline_tags,
         }
     }
-}
 
-impl ContextExtractor {
     pub fn default() -> ContextExtractor {
         let discard_tags = HashSet::from_iter(
             ["script", "style", "noscript", "table"]
@@ -121,11 +108,7 @@ impl ContextExtractor {
     /// and which one is not. However, it does not take into account the style of element (display: block)
     /// and hence has to rely on some heuristics. For example, <canvas> is an inline element, however, it
     /// is often used as block element so this extractor put it in another line.
-    pub fn extract_context<'s>(
-        &self,
-        py: Python,
-        table_el: NodeRef<'s, Node>,
-    ) -> Result<Vec<ContentHierarchy>> {
+    pub fn extract_context(&self, table_el: NodeRef<Node>) -> Result<Vec<ContentHierarchy>> {
         let (tree_before, tree_after) = self.locate_content_before_and_after(table_el)?;
 
         let mut context_before: Vec<RichText> = vec![];
@@ -136,17 +119,13 @@ impl ContextExtractor {
         // self.flatten_tree(&tree_before, &mut context_before);
         // self.flatten_tree(&tree_after, &mut context_after);
 
-        let mut context = vec![ContentHierarchy::new(0, Py::new(py, RichText::empty())?)];
+        let mut context = vec![ContentHierarchy::new(0, RichText::empty())];
         for c in context_before {
             if self.header_elements.contains(c.get_tag()) {
                 let header = c.get_tag()[1..].parse::<usize>().unwrap();
-                context.push(ContentHierarchy::new(header, Py::new(py, c)?));
+                context.push(ContentHierarchy::new(header, c));
             } else {
-                context
-                    .last_mut()
-                    .unwrap()
-                    .content_before
-                    .push(Py::new(py, c)?);
+                context.last_mut().unwrap().content_before.push(c);
                 continue;
             }
         }
@@ -163,12 +142,11 @@ impl ContextExtractor {
         }
         rev_context.reverse();
         context = rev_context;
-        context.last_mut().unwrap().content_after.extend(
-            context_after
-                .into_iter()
-                .map(|c| Py::new(py, c))
-                .collect::<PyResult<Vec<_>>>()?,
-        );
+        context
+            .last_mut()
+            .unwrap()
+            .content_after
+            .extend(context_after);
 
         Ok(context)
     }
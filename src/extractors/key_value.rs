@@ -0,0 +1,239 @@
+use anyhow::Result;
+use ego_tree::{NodeId, NodeRef};
+use hashbrown::HashSet;
+use scraper::{ElementRef, Node, Selector};
+use url::Url;
+
+use crate::extractors::context_v1::ContextExtractor;
+use crate::extractors::text::{get_text, RichTextExtractor, TextOptions};
+use crate::extractors::Document;
+use crate::misc::{convert_attrs, create_id};
+use crate::models::key_value::{KeyValuePair, KeyValueRecord};
+
+/// Extract key-value pairs from definition lists (`dl` with `dt` and `dd`) and infoboxes, which are
+/// tables of class `infobox` or tables whose rows are pairs of a header cell and a data cell.
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+pub struct KeyValueExtractor {
+    context_extractor: ContextExtractor,
+    text_extractor: RichTextExtractor,
+}
+
+impl KeyValueExtractor {
+    pub fn new(context_extractor: ContextExtractor, text_options: TextOptions) -> Self {
+        KeyValueExtractor {
+            context_extractor,
+            text_extractor: RichTextExtractor::new(text_options),
+        }
+    }
+
+    /// Extract key-value records from HTML, in the order they appear in the document. Definition lists and
+    /// infoboxes nested in another record are part of its values and are not extracted separately.
+    pub fn extract_records(
+        &self,
+        doc: &Document,
+        extract_context: bool,
+    ) -> Result<Vec<KeyValueRecord>> {
+        let selector = Selector::parse("dl, table").unwrap();
+        let mut records = vec![];
+        let mut record_els = vec![];
+        let mut record_el_ids = HashSet::<NodeId>::new();
+
+        for el in doc.html.select(&selector) {
+            if el
+                .ancestors()
+                .any(|ancestor| record_el_ids.contains(&ancestor.id()))
+            {
+                continue;
+            }
+
+            let record = if el.value().name() == "dl" {
+                self.extract_definition_list(el)
+            } else {
+                let rows = get_table_rows(el);
+                if !is_infobox(&el, &rows) {
+                    continue;
+                }
+                self.extract_infobox(el, rows)
+            };
+            if record.pairs.is_empty() {
+                continue;
+            }
+            record_el_ids.insert(el.id());
+            records.push(record);
+            record_els.push(el);
+        }
+
+        if extract_context {
+            for (record, el) in records.iter_mut().zip(record_els.iter()) {
+                record.context = self.context_extractor.extract_context(**el)?;
+            }
+        }
+
+        let url = Url::parse(&doc.url)?;
+        let url_converter = self.text_extractor.get_url_converter(doc)?;
+        for (i, record) in records.iter_mut().enumerate() {
+            record.id = create_id(&url, "kv_no", &i.to_string());
+            record.url = doc.url.clone();
+
+            for pair in &mut record.pairs {
+                url_converter.normalize_rich_text(&mut pair.key);
+                url_converter.normalize_rich_text(&mut pair.value);
+            }
+            for content in &mut record.context {
+                url_converter.normalize_content_hierarchy(content);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Extract pairs of a definition list. Following the HTML spec, a group of consecutive `dt`
+    /// is followed by a group of `dd` (the groups may be wrapped in `div`), every key of a group is
+    /// paired with every value of the group. Values without keys are skipped.
+    fn extract_definition_list(&self, dl_el: ElementRef) -> KeyValueRecord {
+        let mut pairs = vec![];
+        let mut items = vec![];
+        for child in dl_el.children() {
+            match child.value() {
+                Node::Element(el) if el.name() == "div" => {
+                    // a div wraps its own groups, keys do not carry over the boundary of the div
+                    self.extract_definition_groups(items.drain(..), &mut pairs);
+                    self.extract_definition_groups(child.children(), &mut pairs);
+                }
+                _ => items.push(child),
+            }
+        }
+        self.extract_definition_groups(items.into_iter(), &mut pairs);
+
+        KeyValueRecord {
+            id: String::new(),
+            url: String::new(),
+            tag: "dl".to_owned(),
+            title: String::new(),
+            attrs: convert_attrs(&dl_el.value().attrs),
+            context: Vec::new(),
+            pairs,
+        }
+    }
+
+    /// Pair keys and values of consecutive `dt` and `dd` items of a definition list
+    fn extract_definition_groups<'a>(
+        &self,
+        items: impl Iterator<Item = NodeRef<'a, Node>>,
+        pairs: &mut Vec<KeyValuePair>,
+    ) {
+        let mut keys = vec![];
+        let mut is_after_value = false;
+
+        for item in items {
+            let el = match item.value() {
+                Node::Element(el) => el,
+                _ => continue,
+            };
+            match el.name() {
+                "dt" => {
+                    if is_after_value {
+                        keys.clear();
+                        is_after_value = false;
+                    }
+                    keys.push(self.text_extractor.get_rich_text(&item));
+                }
+                "dd" => {
+                    is_after_value = true;
+                    let value = self.text_extractor.get_rich_text(&item);
+                    for key in &keys {
+                        pairs.push(KeyValuePair {
+                            key: key.clone(),
+                            value: value.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Extract pairs from rows of two cells of an infobox, other rows (e.g., title, images or section
+    /// headers spanning the table) are skipped
+    fn extract_infobox(
+        &self,
+        table_el: ElementRef,
+        rows: Vec<Vec<NodeRef<Node>>>,
+    ) -> KeyValueRecord {
+        let mut title = table_el
+            .children()
+            .find(|child| matches!(child.value(), Node::Element(el) if el.name() == "caption"))
+            .map(|caption| get_text(&caption).trim().to_owned())
+            .unwrap_or_default();
+        if title.is_empty() {
+            if let Some(row) = rows.first() {
+                if row.len() == 1 && is_header_cell(&row[0]) {
+                    title = self.text_extractor.get_rich_text(&row[0]).text;
+                }
+            }
+        }
+
+        let pairs = rows
+            .iter()
+            .filter(|row| row.len() == 2)
+            .map(|row| KeyValuePair {
+                key: self.text_extractor.get_rich_text(&row[0]),
+                value: self.text_extractor.get_rich_text(&row[1]),
+            })
+            .collect();
+
+        KeyValueRecord {
+            id: String::new(),
+            url: String::new(),
+            tag: "table".to_owned(),
+            title,
+            attrs: convert_attrs(&table_el.value().attrs),
+            context: Vec::new(),
+            pairs,
+        }
+    }
+}
+
+/// Get the cells (td and th) of each row of a table, rows of nested tables are not included
+fn get_table_rows(table_el: ElementRef) -> Vec<Vec<NodeRef<Node>>> {
+    let is_element = |node: &NodeRef<Node>, names: &[&str]| matches!(node.value(), Node::Element(el) if names.contains(&el.name()));
+    table_el
+        .children()
+        .flat_map(|child| {
+            if is_element(&child, &["thead", "tbody", "tfoot"]) {
+                child.children().collect::<Vec<_>>()
+            } else {
+                vec![child]
+            }
+        })
+        .filter(|row| is_element(row, &["tr"]))
+        .map(|row| {
+            row.children()
+                .filter(|cell| is_element(cell, &["td", "th"]))
+                .collect()
+        })
+        .collect()
+}
+
+fn is_header_cell(cell: &NodeRef<Node>) -> bool {
+    matches!(cell.value(), Node::Element(el) if el.name() == "th")
+}
+
+/// Test if a table is an infobox: it has the class `infobox`, or its rows have at most two cells and
+/// at least two rows are pairs of a header cell (the key) and a data cell (the value)
+fn is_infobox(table_el: &ElementRef, rows: &[Vec<NodeRef<Node>>]) -> bool {
+    if table_el
+        .value()
+        .has_class("infobox", scraper::CaseSensitivity::AsciiCaseInsensitive)
+    {
+        return true;
+    }
+    if rows.iter().any(|row| row.len() > 2) {
+        return false;
+    }
+    let pair_rows = rows.iter().filter(|row| row.len() == 2).collect::<Vec<_>>();
+    pair_rows.len() >= 2
+        && pair_rows
+            .iter()
+            .all(|row| is_header_cell(&row[0]) && !is_header_cell(&row[1]))
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use ego_tree::NodeRef;
+use scraper::{ElementRef, Node, Selector};
+use url::Url;
+
+use crate::extractors::context_v1::ContextExtractor;
+use crate::extractors::text::{RichTextExtractor, TextOptions};
+use crate::extractors::Document;
+use crate::misc::{convert_attrs, create_id};
+use crate::models::list::{List, ListItem};
+
+/// Extract standalone lists (`ul` and `ol`) of a page with their nested lists. Lists nested in other lists are
+/// part of their items, and lists inside tables (part of the cells) or `nav` elements (menus) are skipped.
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+pub struct ListExtractor {
+    context_extractor: ContextExtractor,
+    text_extractor: RichTextExtractor,
+    // lists with fewer items (not counting nested items) are skipped
+    min_items: usize,
+}
+
+impl ListExtractor {
+    /// Create an extractor. Nested lists are extracted as children of the items, so `ul` and `ol` are added to
+    /// the discarded tags of the text options and are never part of the text of the items: discarded tags take
+    /// precedence over kept tags (e.g., `ol` and `ul` in the kept tags of `TextOptions::default()`).
+    pub fn new(
+        context_extractor: ContextExtractor,
+        mut text_options: TextOptions,
+        min_items: usize,
+    ) -> Self {
+        text_options
+            .discard_tags
+            .extend(["ul".to_owned(), "ol".to_owned()]);
+
+        ListExtractor {
+            context_extractor,
+            text_extractor: RichTextExtractor::new(text_options),
+            min_items,
+        }
+    }
+
+    /// Extract lists from HTML, in the order they appear in the document.
+    pub fn extract_lists(&self, doc: &Document, extract_context: bool) -> Result<Vec<List>> {
+        let selector = Selector::parse("ul, ol").unwrap();
+        let mut lists = vec![];
+        let mut list_els = vec![];
+
+        for el in doc.html.select(&selector) {
+            let is_skipped = el.ancestors().any(|ancestor| match ancestor.value() {
+                Node::Element(e) => matches!(e.name(), "ul" | "ol" | "li" | "table" | "nav"),
+                _ => false,
+            });
+            if is_skipped {
+                continue;
+            }
+            let list = self.extract_list(el);
+            if list.items.is_empty() || list.items.len() < self.min_items {
+                continue;
+            }
+            lists.push(list);
+            list_els.push(el);
+        }
+
+        if extract_context {
+            for (list, el) in lists.iter_mut().zip(list_els.iter()) {
+                list.context = self.context_extractor.extract_context(**el)?;
+            }
+        }
+
+        let url = Url::parse(&doc.url)?;
+        let url_converter = self.text_extractor.get_url_converter(doc)?;
+        for (i, list) in lists.iter_mut().enumerate() {
+            list.id = create_id(&url, "list_no", &i.to_string());
+            list.url = doc.url.clone();
+
+            let mut stack = list.items.iter_mut().collect::<Vec<_>>();
+            while let Some(item) = stack.pop() {
+                url_converter.normalize_rich_text(&mut item.value);
+                stack.extend(item.children.iter_mut());
+            }
+            for content in &mut list.context {
+                url_converter.normalize_content_hierarchy(content);
+            }
+        }
+
+        Ok(lists)
+    }
+
+    /// Extract a single list
+    ///
+    /// # Arguments
+    ///
+    /// * `list_el` - The ul/ol element
+    pub fn extract_list(&self, list_el: ElementRef) -> List {
+        List {
+            id: String::new(),
+            url: String::new(),
+            tag: list_el.value().name().to_owned(),
+            attrs: convert_attrs(&list_el.value().attrs),
+            context: Vec::new(),
+            items: self.extract_items(*list_el, 0),
+        }
+    }
+
+    /// Extract items of a list, which are the `li` descendants of the list that are not in
+    /// another item or list (they are usually the children of the list)
+    ///
+    /// # Arguments
+    ///
+    /// * `list_ref` - The ul/ol element
+    /// * `depth` - Depth of the list
+    fn extract_items(&self, list_ref: NodeRef<Node>, depth: usize) -> Vec<ListItem> {
+        let list_type = list_ref.value().as_element().unwrap().name().to_owned();
+        let mut items = vec![];
+        let mut stack = list_ref.children().rev().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            let el = match node.value() {
+                Node::Element(el) => el,
+                _ => continue,
+            };
+            match el.name() {
+                "li" => items.push(ListItem {
+                    value: self.text_extractor.get_rich_text(&node),
+                    list_type: list_type.clone(),
+                    depth,
+                    children: find_nested_lists(node)
+                        .into_iter()
+                        .flat_map(|nested_list| self.extract_items(nested_list, depth + 1))
+                        .collect(),
+                }),
+                // lists directly nested in a list (invalid but common) are rendered as
+                // nested lists of the previous item
+                "ul" | "ol" => match items.last_mut() {
+                    Some(item) => item.children.extend(self.extract_items(node, depth + 1)),
+                    None => items.extend(self.extract_items(node, depth)),
+                },
+                _ => stack.extend(node.children().rev()),
+            }
+        }
+        items
+    }
+}
+
+/// Find lists that are descendants of the node but not nested in another list
+fn find_nested_lists(node: NodeRef<Node>) -> Vec<NodeRef<Node>> {
+    let mut lists = vec![];
+    let mut stack = node.children().rev().collect::<Vec<_>>();
+    while let Some(node) = stack.pop() {
+        if let Node::Element(el) = node.value() {
+            if el.name() == "ul" || el.name() == "ol" {
+                lists.push(node);
+                continue;
+            }
+            stack.extend(node.children().rev());
+        }
+    }
+    lists
+}
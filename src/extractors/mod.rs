@@ -1,39 +1,59 @@
-use scraper::Html;
+use ego_tree::NodeId;
+use hashbrown::HashMap;
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::misc::source_positions::{parse_document_with_positions, SourcePosition};
 
 pub mod context_v1;
-pub mod elementrefview;
+pub mod key_value;
+pub mod list;
 pub mod table;
 pub mod text;
 
-use pyo3::prelude::*;
-use scraper::Selector;
-
-use self::elementrefview::ElementRefView;
-
-#[pyclass(module = "rsoup.core", unsendable)]
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core", unsendable))]
 pub struct Document {
     pub url: String,
     pub html: Html,
+    // position of the start tag of each element in the source, only recorded when the document
+    // is created with `new_with_locations`
+    pub positions: Option<HashMap<NodeId, SourcePosition>>,
 }
 
-#[pymethods]
 impl Document {
-    #[new]
     pub fn new(url: String, doc: String) -> Self {
         let html = Html::parse_document(&doc);
-        Document { url, html }
+        Document {
+            url,
+            html,
+            positions: None,
+        }
+    }
+
+    /// Parse the document and record the positions of the elements in the source, so that locations
+    /// of tables, rows and cells include their lines. Parsing is slower than `new` as the source is fed
+    /// to the parser in small chunks.
+    pub fn new_with_locations(url: String, doc: String) -> Self {
+        let (html, positions) = parse_document_with_positions(&doc);
+        Document {
+            url,
+            html,
+            positions: Some(positions),
+        }
     }
 
-    pub fn select(&self, query: &str) -> PyResult<Vec<ElementRefView>> {
-        let selector = Selector::parse(query).map_err(|_err| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid css selector: {}", query))
-        })?;
-        // can't return a wrapper of select because select borrows selector, if we convert its scope to static, the selector
-        // will dropped after this function, rendering the select invalid
-        Ok(self
-            .html
+    /// Get the url that relative links in the document are resolved against, which is the href
+    /// of the first `<base>` element (resolved against the document url) if present, otherwise
+    /// the document url (https://html.spec.whatwg.org/multipage/urls-and-fetching.html#document-base-url).
+    pub fn base_url(&self) -> String {
+        let selector = Selector::parse("base[href]").unwrap();
+        self.html
             .select(&selector)
-            .map(|el| ElementRefView::new(el))
-            .collect::<Vec<_>>())
+            .next()
+            .and_then(|el| {
+                let href = el.value().attr("href")?;
+                Url::parse(&self.url).ok()?.join(href).ok()
+            })
+            .map_or_else(|| self.url.clone(), |url| url.as_str().to_owned())
     }
 }
@@ -1,221 +1,515 @@
-use crate::error::{InvalidCellSpanPyError, OverlapSpanPyError, RSoupError};
+use crate::error::RSoupError;
 use crate::extractors::context_v1::ContextExtractor;
-use crate::extractors::text::{get_rich_text, get_text};
+use crate::extractors::text::{get_text, RichTextExtractor, TextOptions};
 use crate::extractors::Document;
-use crate::misc::convert_attrs;
-use crate::misc::url_converter::URLConverter;
-use crate::models::table::{Cell, Row, Table};
+use crate::misc::css_path::{get_css_path, get_structural_path};
+use crate::misc::source_positions::{parse_document_with_positions, SourcePosition};
+use crate::misc::{convert_attrs, create_id};
+use crate::models::table::{Cell, DiscardedTable, Row, SourceLocation, Table};
 use anyhow::{bail, Result};
-use ego_tree::NodeRef;
-use hashbrown::HashSet;
-use pyo3::prelude::*;
-use scraper::{ElementRef, Node, Selector};
+use ego_tree::{NodeId, NodeRef};
+use hashbrown::{HashMap, HashSet};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use scraper::{node::Element, ElementRef, Html, Node, Selector};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use url::Url;
 
-#[pyclass(module = "rsoup.core")]
+/// How ids of extracted tables are created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableIdScheme {
+    /// `<url>?table_no=<N>` where N is the order of the table among the extracted tables of the page
+    #[default]
+    Index,
+    /// `<url>?table_fp=<H>` where H is a hash of the structural fingerprint of the table (see
+    /// `get_table_fingerprint`), so the id does not change when unrelated tables are added to or removed from the page
+    Fingerprint,
+}
+
+impl FromStr for TableIdScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "index" => Ok(TableIdScheme::Index),
+            "fingerprint" => Ok(TableIdScheme::Fingerprint),
+            _ => bail!("Invalid table id scheme: {}", s),
+        }
+    }
+}
+
+/// Options of `TableExtractor`. Create them from `TableExtractorOptions::default()` and override the fields
+/// that need to be changed.
+#[derive(Debug, Clone)]
+pub struct TableExtractorOptions {
+    // how rich texts of cells are extracted and urls in them are resolved
+    pub text: TextOptions,
+    // use the leading digits of invalid rowspan and colspan (e.g., `2;`) instead of raising errors
+    pub html_error_forgiveness: bool,
+    // whether to extract tables containing other tables, the inner tables are referenced
+    // from the cells containing them
+    pub extract_nested_tables: bool,
+    // whether to span tables following the HTML table processing model, which resolves
+    // overlapping cells instead of discarding the tables
+    pub html_spec_span: bool,
+    // tables whose data table score (see `Table::get_data_table_score`) is below
+    // this threshold are considered layout tables and are discarded
+    pub data_table_threshold: Option<f64>,
+    pub table_id_scheme: TableIdScheme,
+    // whether to record the locations (css path and position in the source) of tables, rows and cells,
+    // positions are only available for documents created with `Document::new_with_locations`
+    pub record_location: bool,
+    // only extract tables matching this css selector (e.g., `table.wikitable`, `#content table`), elements
+    // matching the selector that are not tables are ignored
+    pub include_selector: Option<String>,
+    // skip tables that match or are inside elements matching any of these css selectors (e.g., `.navbox`)
+    pub exclude_selectors: Vec<String>,
+    // whether to extract tables built from other elements (e.g., div) with ARIA table roles
+    // (table, grid, row, cell, columnheader, etc.) or `display: table` inline styles
+    pub extract_aria_tables: bool,
+}
+
+impl Default for TableExtractorOptions {
+    fn default() -> Self {
+        TableExtractorOptions::from_text_options(TextOptions::default())
+    }
+}
+
+impl TableExtractorOptions {
+    /// Options with the given text options, other options keep their default values
+    pub fn from_text_options(text: TextOptions) -> Self {
+        TableExtractorOptions {
+            text,
+            html_error_forgiveness: true,
+            extract_nested_tables: false,
+            html_spec_span: false,
+            data_table_threshold: None,
+            table_id_scheme: TableIdScheme::default(),
+            record_location: false,
+            include_selector: None,
+            exclude_selectors: Vec::new(),
+            extract_aria_tables: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
 pub struct TableExtractor {
-    ignored_tags: HashSet<String>,
-    discard_tags: HashSet<String>,
-    keep_tags: HashSet<String>,
-    only_keep_inline_tags: bool,
+    text_extractor: RichTextExtractor,
     context_extractor: ContextExtractor,
-    html_error_forgiveness: bool,
+    include_selector: Option<Selector>,
+    exclude_selectors: Vec<Selector>,
+    options: TableExtractorOptions,
+    // thread pool of `extract_tables_many`, reused between calls with the same number of threads
+    thread_pool: Mutex<Option<Arc<ThreadPool>>>,
 }
 
-#[pymethods]
 impl TableExtractor {
-    #[new]
-    #[args(
-        "*",
-        ignored_tags = "None",
-        discard_tags = "None",
-        keep_tags = "None",
-        only_keep_inline_tags = "true",
-        html_error_forgiveness = "true"
-    )]
+    /// Create an extractor, return an error if the include or exclude selectors are invalid
     pub fn new(
         context_extractor: ContextExtractor,
-        ignored_tags: Option<Vec<&str>>,
-        discard_tags: Option<Vec<&str>>,
-        keep_tags: Option<Vec<&str>>,
-        only_keep_inline_tags: bool,
-        html_error_forgiveness: bool,
-    ) -> Self {
-        let discard_tags_ = HashSet::from_iter(
-            discard_tags
-                .unwrap_or(["script", "style", "noscript", "table"].to_vec())
-                .into_iter()
-                .map(str::to_owned),
-        );
-        let ignored_tags_ = HashSet::from_iter(
-            ignored_tags
-                .unwrap_or(["div"].to_vec())
-                .into_iter()
-                .map(str::to_owned),
-        );
-        let keep_tags_ = HashSet::from_iter(
-            keep_tags
-                .unwrap_or(["ol", "ul", "li"].to_vec())
-                .into_iter()
-                .map(str::to_owned),
-        );
+        options: TableExtractorOptions,
+    ) -> Result<Self, RSoupError> {
+        let parse = |query: &str| {
+            Selector::parse(query).map_err(|_| RSoupError::InvalidSelectorError(query.to_owned()))
+        };
+        let include_selector = options.include_selector.as_deref().map(parse).transpose()?;
+        let exclude_selectors = options
+            .exclude_selectors
+            .iter()
+            .map(|query| parse(query))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        TableExtractor {
-            ignored_tags: ignored_tags_,
-            discard_tags: discard_tags_,
-            keep_tags: keep_tags_,
-            only_keep_inline_tags,
+        Ok(TableExtractor {
+            text_extractor: RichTextExtractor::new(options.text.clone()),
             context_extractor,
-            html_error_forgiveness,
+            include_selector,
+            exclude_selectors,
+            options,
+            thread_pool: Mutex::new(None),
+        })
+    }
+
+    /// Test if the table is targeted by the include and exclude selectors
+    fn is_selected(&self, table_el: &ElementRef) -> bool {
+        if !self.is_table(**table_el) {
+            return false;
         }
+        if self.exclude_selectors.is_empty() {
+            return true;
+        }
+        std::iter::once(*table_el)
+            .chain(table_el.ancestors().filter_map(ElementRef::wrap))
+            .all(|el| !self.exclude_selectors.iter().any(|sel| sel.matches(&el)))
     }
 
-    #[args(auto_span = "true", auto_pad = "true", extract_context = "true")]
-    fn extract(
+    /// Test if the node is a table: a table element or, when ARIA tables are extracted, an element
+    /// with a table role or `display: table` style that is not an element of HTML tables (e.g., td)
+    /// and has at least one row
+    fn is_table(&self, node: NodeRef<Node>) -> bool {
+        let el = match node.value() {
+            Node::Element(el) => el,
+            _ => return false,
+        };
+        if el.name() == "table" {
+            return true;
+        }
+        self.options.extract_aria_tables
+            && !HTML_TABLE_INTERNAL_TAGS.contains(&el.name())
+            && get_aria_role(el) == AriaRole::Table
+            && has_aria_rows(node)
+    }
+
+    /// Extract tables from HTML.
+    pub fn extract_tables(
         &self,
-        py: Python,
-        url: String,
-        doc: String,
+        doc: &Document,
         auto_span: bool,
         auto_pad: bool,
         extract_context: bool,
-    ) -> PyResult<Vec<Table>> {
-        Ok(self.extract_tables(
-            py,
-            &Document::new(url, doc),
-            auto_span,
-            auto_pad,
-            extract_context,
-        )?)
+    ) -> Result<Vec<Table>> {
+        let (tables, _) =
+            self.extract_tables_with_diagnostics(doc, auto_span, auto_pad, extract_context)?;
+        Ok(tables)
     }
-}
 
-impl TableExtractor {
-    /// Extract tables from HTML.
-    pub fn extract_tables<'t>(
+    /// Extract tables from many pages in parallel. Each page is a pair of (url, html) and is parsed
+    /// in the thread extracting it. The result of each page (its tables or the error extracting them)
+    /// is returned in the same order as the pages, so one bad page does not fail the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - (url, html) of the pages
+    /// * `num_threads` - size of the thread pool, use rayon's global pool if not provided. The pool is
+    ///   created on the first call and reused by later calls with the same number of threads
+    pub fn extract_tables_many(
         &self,
-        py: Python,
-        doc: &'t Document,
+        pages: &[(String, String)],
         auto_span: bool,
         auto_pad: bool,
         extract_context: bool,
-    ) -> Result<Vec<Table>> {
+        num_threads: Option<usize>,
+    ) -> Result<Vec<Result<Vec<Table>>>> {
+        let extract = || {
+            pages
+                .par_iter()
+                .map(|(url, html)| {
+                    let doc = self.parse_document(url.clone(), html);
+                    self.extract_tables(&doc, auto_span, auto_pad, extract_context)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Ok(match num_threads {
+            None => extract(),
+            Some(num_threads) => self.get_thread_pool(num_threads)?.install(extract),
+        })
+    }
+
+    /// Parse a page into a document, the positions of elements in the source are only recorded
+    /// (see `Document::new_with_locations`) when the extractor records locations
+    pub fn parse_document(&self, url: String, html: &str) -> Document {
+        let (html, positions) = if self.options.record_location {
+            let (html, positions) = parse_document_with_positions(html);
+            (html, Some(positions))
+        } else {
+            (Html::parse_document(html), None)
+        };
+        Document {
+            url,
+            html,
+            positions,
+        }
+    }
+
+    /// Get the thread pool of `extract_tables_many`, the pool is only rebuilt when the number of threads changes
+    fn get_thread_pool(&self, num_threads: usize) -> Result<Arc<ThreadPool>> {
+        let mut thread_pool = self.thread_pool.lock().unwrap();
+        if let Some(pool) = thread_pool.as_ref() {
+            if pool.current_num_threads() == num_threads {
+                return Ok(pool.clone());
+            }
+        }
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(num_threads).build()?);
+        *thread_pool = Some(pool.clone());
+        Ok(pool)
+    }
+
+    /// Extract tables from HTML. Also return tables that are discarded during
+    /// the extraction and the reasons why they are discarded.
+    pub fn extract_tables_with_diagnostics(
+        &self,
+        doc: &Document,
+        auto_span: bool,
+        auto_pad: bool,
+        extract_context: bool,
+    ) -> Result<(Vec<Table>, Vec<DiscardedTable>)> {
         let tree = &doc.html;
 
-        let selector = Selector::parse("table").unwrap();
+        let selector = if self.options.extract_aria_tables {
+            Selector::parse("table, [role], [style]").unwrap()
+        } else {
+            Selector::parse("table").unwrap()
+        };
+        let include_selector = self.include_selector.as_ref().unwrap_or(&selector);
         let mut tables = vec![];
         let mut table_els = vec![];
-        let mut table_nos = vec![];
+        let mut table_indices = vec![];
+        let mut nested_cells = vec![];
+        let mut discarded_tables = vec![];
 
-        for el in tree.select(&selector) {
-            if el.select(&selector).next().is_some() {
+        let is_targeted = |el: &ElementRef| include_selector.matches(el) && self.is_selected(el);
+
+        for (table_index, el) in tree
+            .select(include_selector)
+            .filter(|el| self.is_selected(el))
+            .enumerate()
+        {
+            // only tables targeted by the selectors count as nested tables, so that a table is not lost
+            // because of an inner table that is not extracted anyway. ARIA tables inside an HTML table
+            // do not make it nested as they are not part of its structure
+            let is_html_table = el.value().name() == "table";
+            if !self.options.extract_nested_tables
+                && el
+                    .descendants()
+                    .skip(1)
+                    .filter_map(ElementRef::wrap)
+                    .any(|child| {
+                        (!is_html_table || child.value().name() == "table") && is_targeted(&child)
+                    })
+            {
+                discarded_tables.push(DiscardedTable {
+                    table_index,
+                    path: get_css_path(&el),
+                    reason: "nested".to_owned(),
+                    span_position: None,
+                });
                 continue;
             }
-            let table = self.extract_non_nested_table(py, el)?;
+            let mut table_nested_cells = vec![];
+            let table = if el.value().name() == "table" {
+                self.extract_table(el, doc.positions.as_ref(), &mut table_nested_cells)?
+            } else {
+                self.extract_aria_table(el, doc.positions.as_ref(), &mut table_nested_cells)?
+            };
             // skip if no rows or columns
-            if table.rows.len() == 0 || table.rows.iter().all(|r| r.borrow(py).cells.len() == 0) {
+            if table.rows.is_empty() || table.rows.iter().all(|r| r.cells.is_empty()) {
+                discarded_tables.push(DiscardedTable {
+                    table_index,
+                    path: get_css_path(&el),
+                    reason: "empty".to_owned(),
+                    span_position: None,
+                });
                 continue;
             }
+            nested_cells.extend(
+                table_nested_cells
+                    .into_iter()
+                    .map(|(ri, ci, nested_table_els)| (tables.len(), ri, ci, nested_table_els)),
+            );
             tables.push(table);
             table_els.push(el);
-            table_nos.push(table_nos.len());
+            table_indices.push(table_index);
+        }
+
+        // ids are created before the tables are spanned and filtered so that they do not depend on these steps
+        let url = Url::parse(&doc.url)?;
+        let mut table_ids = match self.options.table_id_scheme {
+            TableIdScheme::Index => (0..tables.len())
+                .map(|table_no| create_id(&url, "table_no", &table_no.to_string()))
+                .collect::<Vec<_>>(),
+            TableIdScheme::Fingerprint => {
+                let mut counter = HashMap::new();
+                tables
+                    .iter()
+                    .zip(table_els.iter())
+                    .map(|(table, el)| {
+                        let fingerprint = get_table_fingerprint(el, table);
+                        // tables with the same fingerprint are distinguished by their order
+                        let count = counter.entry(fingerprint.clone()).or_insert(0);
+                        *count += 1;
+                        let value = if *count == 1 {
+                            fingerprint
+                        } else {
+                            format!("{}-{}", fingerprint, *count - 1)
+                        };
+                        create_id(&url, "table_fp", &value)
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let has_nested_cells = !nested_cells.is_empty();
+        if has_nested_cells {
+            // link the cells to the tables nested inside them before the tables are spanned so that copies of
+            // merged cells keep the links. links to tables discarded by the next steps are removed at the end
+            let table_id_by_el = table_els
+                .iter()
+                .zip(table_ids.iter())
+                .map(|(el, table_id)| (el.id(), table_id))
+                .collect::<HashMap<_, _>>();
+            for (ti, ri, ci, nested_table_els) in nested_cells {
+                tables[ti].rows[ri].cells[ci].nested_table_ids = nested_table_els
+                    .into_iter()
+                    .filter_map(|el_id| table_id_by_el.get(&el_id))
+                    .map(|table_id| (*table_id).clone())
+                    .collect();
+            }
         }
 
         if auto_span {
             let mut new_tables = Vec::with_capacity(tables.len());
             let mut new_table_els = Vec::with_capacity(tables.len());
-            let mut new_table_nos = Vec::with_capacity(tables.len());
+            let mut new_table_ids = Vec::with_capacity(tables.len());
+            let mut new_table_indices = Vec::with_capacity(tables.len());
 
             for (i, tbl) in tables.iter().enumerate() {
-                match tbl.span(py) {
+                let spanned_tbl = if self.options.html_spec_span {
+                    Ok(tbl.span_html_spec())
+                } else {
+                    tbl.span()
+                };
+                let (reason, row, cell, col) = match spanned_tbl {
                     Ok(new_tbl) => {
                         new_tables.push(new_tbl);
                         new_table_els.push(table_els[i]);
-                        new_table_nos.push(i);
+                        new_table_ids.push(table_ids[i].clone());
+                        new_table_indices.push(table_indices[i]);
+                        continue;
                     }
-                    Err(err) => {
-                        if !err.is_instance_of::<OverlapSpanPyError>(py)
-                            && !err.is_instance_of::<InvalidCellSpanPyError>(py)
-                        {
-                            bail!(err);
-                        }
+                    Err(RSoupError::OverlapSpanError { row, cell, col }) => {
+                        ("overlap_span", row, cell, col)
                     }
-                }
+                    Err(RSoupError::InvalidCellSpanError { row, cell, col }) => {
+                        ("invalid_cell_span", row, cell, col)
+                    }
+                    Err(err) => bail!(err),
+                };
+                discarded_tables.push(DiscardedTable {
+                    table_index: table_indices[i],
+                    path: get_css_path(&table_els[i]),
+                    reason: reason.to_owned(),
+                    span_position: Some((row, cell, col)),
+                });
             }
             tables = new_tables;
             table_els = new_table_els;
-            table_nos = new_table_nos;
+            table_ids = new_table_ids;
+            table_indices = new_table_indices;
         }
 
         if auto_pad {
             tables = tables
                 .into_iter()
-                .map(|tbl| Ok(tbl.pad(py)?.unwrap_or(tbl)))
-                .collect::<PyResult<Vec<_>>>()?
+                .map(|tbl| tbl.pad().unwrap_or(tbl))
+                .collect();
+        }
+
+        if let Some(threshold) = self.options.data_table_threshold {
+            let mut new_tables = Vec::with_capacity(tables.len());
+            let mut new_table_els = Vec::with_capacity(tables.len());
+            let mut new_table_ids = Vec::with_capacity(tables.len());
+
+            for (i, tbl) in tables.into_iter().enumerate() {
+                if tbl.get_data_table_score() < threshold {
+                    discarded_tables.push(DiscardedTable {
+                        table_index: table_indices[i],
+                        path: get_css_path(&table_els[i]),
+                        reason: "layout".to_owned(),
+                        span_position: None,
+                    });
+                    continue;
+                }
+                new_tables.push(tbl);
+                new_table_els.push(table_els[i]);
+                new_table_ids.push(table_ids[i].clone());
+            }
+            tables = new_tables;
+            table_els = new_table_els;
+            table_ids = new_table_ids;
         }
 
         if extract_context {
             for i in 0..tables.len() {
-                tables[i].context = self
-                    .context_extractor
-                    .extract_context(py, *table_els[i])?
-                    .into_iter()
-                    .map(|x| Py::new(py, x))
-                    .collect::<PyResult<Vec<_>>>()?;
+                tables[i].context = self.context_extractor.extract_context(*table_els[i])?;
             }
         }
 
         // update table id
-        let mut url = Url::parse(&doc.url)?;
-        let mut query = match url.query() {
-            None => "table_no=".as_bytes().to_vec(),
-            Some(q) => {
-                let mut v = q.as_bytes().to_vec();
-                v.extend_from_slice("&table_no=".as_bytes());
-                v
-            }
-        };
-        let query_len = query.len();
-
-        for (i, tbl) in tables.iter_mut().enumerate() {
-            query.extend_from_slice(table_nos[i].to_string().as_bytes());
-            url.set_query(Some(std::str::from_utf8(&query)?));
-            tbl.id = url.as_str().to_owned();
-            query.truncate(query_len);
+        for (tbl, table_id) in tables.iter_mut().zip(table_ids) {
+            tbl.id = table_id;
             tbl.url = doc.url.to_owned();
         }
 
-        // convert relative urls to absolute urls
-        let url_converter = URLConverter::new(doc.url.to_owned())?;
+        if has_nested_cells {
+            // remove links to nested tables that are discarded because they cannot be spanned or are layout tables
+            let table_ids = tables
+                .iter()
+                .map(|tbl| tbl.id.clone())
+                .collect::<HashSet<_>>();
+            for table in &mut tables {
+                for row in &mut table.rows {
+                    for cell in &mut row.cells {
+                        cell.nested_table_ids
+                            .retain(|table_id| table_ids.contains(table_id));
+                    }
+                }
+            }
+        }
+
+        // convert relative urls to absolute urls, which are relative to the base url of the document
+        let url_converter = self.text_extractor.get_url_converter(doc)?;
         for table in &mut tables {
             for row in &mut table.rows {
-                for cell in &mut (row.borrow_mut(py)).cells {
-                    url_converter
-                        .normalize_rich_text(&mut *cell.borrow_mut(py).value.borrow_mut(py));
+                for cell in &mut row.cells {
+                    url_converter.normalize_rich_text(&mut cell.value);
                 }
             }
 
             for content in &mut table.context {
-                for line in &mut content.borrow_mut(py).content_before {
-                    url_converter.normalize_rich_text(&mut *line.borrow_mut(py));
-                }
-                for line in &mut content.borrow_mut(py).content_after {
-                    url_converter.normalize_rich_text(&mut *line.borrow_mut(py));
-                }
+                url_converter.normalize_content_hierarchy(content);
             }
         }
 
-        Ok(tables)
+        Ok((tables, discarded_tables))
     }
 
     /// Extract content of a single table
     ///
+    /// Rows are collected from every row group (thead, tbody, tfoot) and from `tr` elements
+    /// that are direct children of the table. Similar to the HTML table processing model,
+    /// rows of tfoot are moved to the end of the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_el` - The table element
+    pub fn extract_non_nested_table(&self, table_el: ElementRef) -> Result<Table> {
+        self.extract_table(table_el, None, &mut Vec::new())
+    }
+
+    /// Extract content of a single table. When nested tables are extracted, positions (row index, cell index)
+    /// of cells containing tables are pushed to `nested_cells` together with the tables directly nested in them.
+    ///
     /// # Arguments
     ///
     /// * `table_el` - The table element
-    pub fn extract_non_nested_table(&self, py: Python, table_el: ElementRef) -> Result<Table> {
+    /// * `positions` - Positions of the elements in the source, used when locations are recorded
+    /// * `nested_cells` - Output of cells containing nested tables
+    fn extract_table(
+        &self,
+        table_el: ElementRef,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+        nested_cells: &mut Vec<(usize, usize, Vec<NodeId>)>,
+    ) -> Result<Table> {
         let mut caption: String = "".to_owned();
         let mut rows = vec![];
+        let mut footer_rows = vec![];
+        // same as `nested_cells` but cells are located by their rows in `rows` or `footer_rows`
+        let mut row_nested_cells = vec![];
+        let mut footer_row_nested_cells = vec![];
+        let mut n_row_groups = 0;
+        // whether the previous row group is an implicit tbody of rows without a row group
+        let mut is_implicit_group = false;
 
         for child_ref in table_el.children() {
             let child = child_ref.value();
@@ -224,44 +518,70 @@ impl TableExtractor {
             }
 
             let cel = child.as_element().unwrap();
-            if cel.name() == "caption" {
-                caption = get_text(&child_ref);
-                continue;
-            }
-
-            if cel.name() != "thead" && cel.name() != "tbody" {
-                debug_assert!(cel.name() == "style");
-                continue;
-            }
-
-            for row_ref in child_ref.children() {
-                if let Node::Element(row_el) = row_ref.value() {
-                    if row_el.name() != "tr" {
-                        debug_assert!(row_el.name() == "style");
-                        continue;
+            match cel.name() {
+                "caption" => {
+                    caption = get_text(&child_ref);
+                }
+                "tr" => {
+                    // consecutive rows without a row group belong to an implicit tbody
+                    if !is_implicit_group {
+                        n_row_groups += 1;
+                        is_implicit_group = true;
                     }
-
-                    let mut cells = vec![];
-                    for cell_ref in row_ref.children() {
-                        if let Node::Element(cell_el) = cell_ref.value() {
-                            if cell_el.name() != "td" && cell_el.name() != "th" {
-                                debug_assert!(cell_el.name() == "style");
+                    rows.push(self.extract_row(
+                        child_ref,
+                        "tbody",
+                        n_row_groups - 1,
+                        rows.len(),
+                        positions,
+                        &mut row_nested_cells,
+                    )?);
+                }
+                "thead" | "tbody" | "tfoot" => {
+                    let section = cel.name();
+                    let row_group = n_row_groups;
+                    n_row_groups += 1;
+                    is_implicit_group = false;
+                    for row_ref in child_ref.children() {
+                        if let Node::Element(row_el) = row_ref.value() {
+                            if row_el.name() != "tr" {
                                 continue;
                             }
-                            cells.push(Py::new(py, self.extract_cell(py, cell_ref)?)?);
+                            if section == "tfoot" {
+                                footer_rows.push(self.extract_row(
+                                    row_ref,
+                                    section,
+                                    row_group,
+                                    footer_rows.len(),
+                                    positions,
+                                    &mut footer_row_nested_cells,
+                                )?);
+                            } else {
+                                rows.push(self.extract_row(
+                                    row_ref,
+                                    section,
+                                    row_group,
+                                    rows.len(),
+                                    positions,
+                                    &mut row_nested_cells,
+                                )?);
+                            }
                         }
                     }
-
-                    rows.push(Py::new(
-                        py,
-                        Row {
-                            cells,
-                            attrs: convert_attrs(&row_el.attrs),
-                        },
-                    )?);
+                }
+                _ => {
+                    // colgroup, style, etc.
+                    continue;
                 }
             }
         }
+        nested_cells.extend(row_nested_cells);
+        nested_cells.extend(
+            footer_row_nested_cells
+                .into_iter()
+                .map(|(ri, ci, nested_table_els)| (rows.len() + ri, ci, nested_table_els)),
+        );
+        rows.extend(footer_rows);
 
         Ok(Table {
             id: String::new(),
@@ -270,6 +590,55 @@ impl TableExtractor {
             attrs: convert_attrs(&table_el.value().attrs),
             context: Vec::new(),
             rows,
+            location: self.get_location(&table_el, positions),
+        })
+    }
+
+    /// Extract a single row from a tr tag
+    ///
+    /// # Arguments
+    ///
+    /// * `row_ref` - tr tag
+    /// * `section` - the section (thead, tbody or tfoot) of the row group containing the row
+    /// * `row_group` - index of the row group containing the row
+    /// * `row_index` - index of the row, used to locate cells in `nested_cells`
+    /// * `positions` - Positions of the elements in the source, used when locations are recorded
+    /// * `nested_cells` - Output of cells containing nested tables
+    fn extract_row(
+        &self,
+        row_ref: NodeRef<Node>,
+        section: &str,
+        row_group: usize,
+        row_index: usize,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+        nested_cells: &mut Vec<(usize, usize, Vec<NodeId>)>,
+    ) -> Result<Row> {
+        let row_el = row_ref.value().as_element().expect("Expected element");
+        debug_assert!(row_el.name() == "tr");
+
+        let mut cells = vec![];
+        for cell_ref in row_ref.children() {
+            if let Node::Element(cell_el) = cell_ref.value() {
+                if cell_el.name() != "td" && cell_el.name() != "th" {
+                    debug_assert!(cell_el.name() == "style");
+                    continue;
+                }
+                if self.options.extract_nested_tables {
+                    let nested_table_els = self.find_nested_tables(cell_ref);
+                    if !nested_table_els.is_empty() {
+                        nested_cells.push((row_index, cells.len(), nested_table_els));
+                    }
+                }
+                cells.push(self.extract_cell(cell_ref, positions)?);
+            }
+        }
+
+        Ok(Row {
+            cells,
+            attrs: convert_attrs(&row_el.attrs),
+            section: section.to_owned(),
+            row_group,
+            location: self.get_location(&row_ref, positions),
         })
     }
 
@@ -278,17 +647,41 @@ impl TableExtractor {
     /// # Arguments
     ///
     /// * `cell` - td/th tag
-    fn extract_cell(&self, py: Python, cell: NodeRef<Node>) -> Result<Cell> {
+    /// * `positions` - Positions of the elements in the source, used when locations are recorded
+    fn extract_cell(
+        &self,
+        cell: NodeRef<Node>,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+    ) -> Result<Cell> {
         let el = cell.value().as_element().expect("Expected element");
         debug_assert!(el.name() == "td" || el.name() == "th");
 
         let is_header = el.name() == "th";
-        let raw_colspan = el.attr("colspan").unwrap_or("1").trim();
-        let raw_rowspan = el.attr("rowspan").unwrap_or("1").trim();
+        self.new_cell(
+            cell,
+            is_header,
+            el.attr("rowspan"),
+            el.attr("colspan"),
+            positions,
+        )
+    }
 
-        let colspan = if raw_colspan == "" {
+    /// Create a cell from its element, parsing the raw rowspan and colspan attributes
+    fn new_cell(
+        &self,
+        cell: NodeRef<Node>,
+        is_header: bool,
+        raw_rowspan: Option<&str>,
+        raw_colspan: Option<&str>,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+    ) -> Result<Cell> {
+        let el = cell.value().as_element().expect("Expected element");
+        let raw_colspan = raw_colspan.unwrap_or("1").trim();
+        let raw_rowspan = raw_rowspan.unwrap_or("1").trim();
+
+        let colspan = if raw_colspan.is_empty() {
             1
-        } else if self.html_error_forgiveness {
+        } else if self.options.html_error_forgiveness {
             atoi::atoi::<u16>(raw_colspan.as_bytes()).unwrap_or(1)
         } else {
             // convert
@@ -296,9 +689,9 @@ impl TableExtractor {
                 .parse::<u16>()
                 .map_err(|_| RSoupError::InvalidColSpanError(raw_colspan.to_owned()))?
         };
-        let rowspan = if raw_rowspan == "" {
+        let rowspan = if raw_rowspan.is_empty() {
             1
-        } else if self.html_error_forgiveness {
+        } else if self.options.html_error_forgiveness {
             atoi::atoi::<u16>(raw_rowspan.as_bytes()).unwrap_or(1)
         } else {
             raw_rowspan
@@ -310,17 +703,328 @@ impl TableExtractor {
             is_header,
             rowspan,
             colspan,
-            value: Py::new(
-                py,
-                get_rich_text(
-                    &cell,
-                    &self.ignored_tags,
-                    self.only_keep_inline_tags,
-                    &self.discard_tags,
-                    &self.keep_tags,
-                ),
-            )?,
+            value: self.text_extractor.get_rich_text(&cell),
             attrs: convert_attrs(&el.attrs),
+            nested_table_ids: Vec::new(),
+            span_conflict: false,
+            origin: None,
+            location: self.get_location(&cell, positions),
+        })
+    }
+
+    /// Extract content of a table built with ARIA roles (table, grid, treegrid) or `display: table` styles.
+    ///
+    /// Rows (role `row` or `display: table-row`) are searched in the descendants of the table, and cells
+    /// (roles `cell`, `gridcell`, `columnheader`, `rowheader` or `display: table-cell`) in the descendants of
+    /// the rows, without entering nested tables. Rows in `display: table-header-group` and `table-footer-group`
+    /// elements belong to thead and tfoot, and similar to the HTML tables, rows of tfoot are moved to the end
+    /// of the table. The caption is the element with role `caption` (or `display: table-caption`), or the
+    /// `aria-label` attribute of the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_el` - The table element
+    /// * `positions` - Positions of the elements in the source, used when locations are recorded
+    /// * `nested_cells` - Output of cells containing nested tables
+    fn extract_aria_table(
+        &self,
+        table_el: ElementRef,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+        nested_cells: &mut Vec<(usize, usize, Vec<NodeId>)>,
+    ) -> Result<Table> {
+        let mut caption = None;
+        let mut rows = vec![];
+        let mut footer_rows = vec![];
+        let mut row_nested_cells = vec![];
+        let mut footer_row_nested_cells = vec![];
+
+        let mut stack = table_el
+            .children()
+            .rev()
+            .map(|node| (node, "tbody", 0))
+            .collect::<Vec<_>>();
+        // rows that are not in a row group belong to the implicit group 0
+        let mut n_row_groups = 1;
+        while let Some((node, section, row_group)) = stack.pop() {
+            let el = match node.value() {
+                Node::Element(el) => el,
+                _ => continue,
+            };
+            if self.is_table(node) {
+                continue;
+            }
+            match get_aria_role(el) {
+                AriaRole::Caption => {
+                    if caption.is_none() {
+                        caption = Some(get_text(&node));
+                    }
+                }
+                AriaRole::Row if section == "tfoot" => {
+                    footer_rows.push(self.extract_aria_row(
+                        node,
+                        section,
+                        row_group,
+                        footer_rows.len(),
+                        positions,
+                        &mut footer_row_nested_cells,
+                    )?);
+                }
+                AriaRole::Row => {
+                    rows.push(self.extract_aria_row(
+                        node,
+                        section,
+                        row_group,
+                        rows.len(),
+                        positions,
+                        &mut row_nested_cells,
+                    )?);
+                }
+                AriaRole::RowGroup(section) => {
+                    let row_group = n_row_groups;
+                    n_row_groups += 1;
+                    stack.extend(node.children().rev().map(|node| (node, section, row_group)));
+                }
+                _ => {
+                    stack.extend(node.children().rev().map(|node| (node, section, row_group)));
+                }
+            }
+        }
+        nested_cells.extend(row_nested_cells);
+        nested_cells.extend(
+            footer_row_nested_cells
+                .into_iter()
+                .map(|(ri, ci, nested_table_els)| (rows.len() + ri, ci, nested_table_els)),
+        );
+        rows.extend(footer_rows);
+
+        Ok(Table {
+            id: String::new(),
+            url: String::new(),
+            caption: caption
+                .or_else(|| table_el.value().attr("aria-label").map(str::to_owned))
+                .unwrap_or_default(),
+            attrs: convert_attrs(&table_el.value().attrs),
+            context: Vec::new(),
+            rows,
+            location: self.get_location(&table_el, positions),
+        })
+    }
+
+    /// Extract a single row of an ARIA table, the rowspan and colspan of cells are read from
+    /// the `aria-rowspan` and `aria-colspan` attributes
+    ///
+    /// # Arguments
+    ///
+    /// * `row_ref` - the row element
+    /// * `section` - the section (thead, tbody or tfoot) of the row group containing the row
+    /// * `row_group` - index of the row group containing the row
+    /// * `row_index` - index of the row, used to locate cells in `nested_cells`
+    /// * `positions` - Positions of the elements in the source, used when locations are recorded
+    /// * `nested_cells` - Output of cells containing nested tables
+    fn extract_aria_row(
+        &self,
+        row_ref: NodeRef<Node>,
+        section: &str,
+        row_group: usize,
+        row_index: usize,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+        nested_cells: &mut Vec<(usize, usize, Vec<NodeId>)>,
+    ) -> Result<Row> {
+        let row_el = row_ref.value().as_element().expect("Expected element");
+
+        let mut cells = vec![];
+        let mut stack = row_ref.children().rev().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            let el = match node.value() {
+                Node::Element(el) => el,
+                _ => continue,
+            };
+            if self.is_table(node) {
+                continue;
+            }
+            match get_aria_role(el) {
+                AriaRole::Cell { is_header } => {
+                    if self.options.extract_nested_tables {
+                        let nested_table_els = self.find_nested_tables(node);
+                        if !nested_table_els.is_empty() {
+                            nested_cells.push((row_index, cells.len(), nested_table_els));
+                        }
+                    }
+                    cells.push(self.new_cell(
+                        node,
+                        is_header,
+                        el.attr("aria-rowspan"),
+                        el.attr("aria-colspan"),
+                        positions,
+                    )?);
+                }
+                AriaRole::Row => continue,
+                _ => stack.extend(node.children().rev()),
+            }
+        }
+
+        Ok(Row {
+            cells,
+            attrs: convert_attrs(&row_el.attrs),
+            section: section.to_owned(),
+            row_group,
+            location: self.get_location(&row_ref, positions),
+        })
+    }
+
+    /// Find tables that are descendants of the node but not nested in another table
+    fn find_nested_tables(&self, node: NodeRef<Node>) -> Vec<NodeId> {
+        let mut tables = vec![];
+        let mut stack = node.children().rev().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if self.is_table(node) {
+                tables.push(node.id());
+                continue;
+            }
+            if node.value().is_element() {
+                stack.extend(node.children().rev());
+            }
+        }
+        tables
+    }
+
+    /// Get location of the element if locations are recorded
+    fn get_location(
+        &self,
+        node: &NodeRef<Node>,
+        positions: Option<&HashMap<NodeId, SourcePosition>>,
+    ) -> Option<SourceLocation> {
+        if !self.options.record_location {
+            return None;
+        }
+        let position = positions.and_then(|positions| positions.get(&node.id()));
+        Some(SourceLocation::new(
+            get_css_path(node),
+            position.map(|p| p.line),
+            position.map(|p| p.column),
+        ))
+    }
+}
+
+/// Get a fingerprint of a table from its structure: the path to the table (without positions of the elements),
+/// its id and classes, its caption, its number of columns and the content of its header rows (the leading rows of
+/// header cells). Data rows are not used so that the fingerprint of a table without header rows does not change
+/// when its data is edited. The fingerprint is a hex string of a 64-bit FNV-1a hash, which is stable across runs
+/// and platforms.
+pub fn get_table_fingerprint(table_el: &ElementRef, table: &Table) -> String {
+    let header_rows = table.rows.iter().take_while(|row| {
+        row.section == "thead"
+            || (!row.cells.is_empty() && row.cells.iter().all(|cell| cell.is_header))
+    });
+    let ncols = table
+        .rows
+        .iter()
+        .map(|row| row.cells.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut content = get_structural_path(table_el);
+    content.push('\n');
+    content.push_str(table.caption.trim());
+    content.push('\n');
+    content.push_str(&ncols.to_string());
+    for row in header_rows {
+        content.push('\n');
+        content.push_str(
+            &row.cells
+                .iter()
+                .map(|cell| cell.value.text.trim())
+                .collect::<Vec<_>>()
+                .join("\t"),
+        );
+    }
+
+    // FNV-1a
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Table roles of elements, from their ARIA role or their `display` inline style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AriaRole {
+    Table,
+    Caption,
+    // the section (thead, tbody or tfoot) of rows in the group
+    RowGroup(&'static str),
+    Row,
+    Cell { is_header: bool },
+    None,
+}
+
+/// Elements of HTML tables, which are never ARIA tables even if they have a table role or `display: table` style
+const HTML_TABLE_INTERNAL_TAGS: [&str; 9] = [
+    "caption", "colgroup", "col", "thead", "tbody", "tfoot", "tr", "td", "th",
+];
+
+/// Test if an ARIA table candidate has a row, rows of HTML tables and other ARIA tables nested in it are not counted
+fn has_aria_rows(node: NodeRef<Node>) -> bool {
+    let mut stack = node.children().rev().collect::<Vec<_>>();
+    while let Some(node) = stack.pop() {
+        if let Node::Element(el) = node.value() {
+            if el.name() == "table" {
+                continue;
+            }
+            match get_aria_role(el) {
+                AriaRole::Row => return true,
+                AriaRole::Table if !HTML_TABLE_INTERNAL_TAGS.contains(&el.name()) => continue,
+                _ => stack.extend(node.children().rev()),
+            }
+        }
+    }
+    false
+}
+
+/// Get the table role of an element, the `role` attribute takes precedence over the `display` style
+fn get_aria_role(el: &Element) -> AriaRole {
+    if let Some(role) = el.attr("role") {
+        // the first recognized token is used (https://www.w3.org/TR/wai-aria-1.2/#host_general_role)
+        for token in role.split_ascii_whitespace() {
+            let role = match token.to_ascii_lowercase().as_str() {
+                "table" | "grid" | "treegrid" => AriaRole::Table,
+                "caption" => AriaRole::Caption,
+                "rowgroup" => AriaRole::RowGroup("tbody"),
+                "row" => AriaRole::Row,
+                "cell" | "gridcell" => AriaRole::Cell { is_header: false },
+                "columnheader" | "rowheader" => AriaRole::Cell { is_header: true },
+                _ => continue,
+            };
+            return role;
+        }
+    }
+
+    // the last declaration wins
+    let display = el.attr("style").and_then(|style| {
+        style.rsplit(';').find_map(|decl| {
+            let (name, value) = decl.split_once(':')?;
+            if !name.trim().eq_ignore_ascii_case("display") {
+                return None;
+            }
+            Some(
+                value
+                    .trim()
+                    .trim_end_matches("!important")
+                    .trim()
+                    .to_ascii_lowercase(),
+            )
         })
+    });
+    match display.as_deref() {
+        Some("table") | Some("inline-table") => AriaRole::Table,
+        Some("table-caption") => AriaRole::Caption,
+        Some("table-header-group") => AriaRole::RowGroup("thead"),
+        Some("table-row-group") => AriaRole::RowGroup("tbody"),
+        Some("table-footer-group") => AriaRole::RowGroup("tfoot"),
+        Some("table-row") => AriaRole::Row,
+        Some("table-cell") => AriaRole::Cell { is_header: false },
+        _ => AriaRole::None,
     }
 }
@@ -2,10 +2,12 @@ mod get_rich_text;
 pub mod get_text_v1;
 pub mod get_text_v2;
 pub mod line;
+mod rich_text_extractor;
 use phf::{phf_set, Set};
 
 pub use self::get_rich_text::{get_rich_text, get_rich_text_from_seq};
 pub use self::get_text_v2::get_text;
+pub use self::rich_text_extractor::{RichTextExtractor, TextOptions};
 
 /// list of inline elements that will be rendered in same line except <br> tags
 /// https://developer.mozilla.org/en-US/docs/Web/HTML/Inline_elements
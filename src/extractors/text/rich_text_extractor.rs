@@ -0,0 +1,81 @@
+use anyhow::Result;
+use ego_tree::NodeRef;
+use hashbrown::HashSet;
+use scraper::Node;
+
+use super::get_rich_text;
+use crate::extractors::Document;
+use crate::misc::url_converter::{URLCanonicalization, URLConverter, DEFAULT_URL_ATTRS};
+use crate::models::rich_text::RichText;
+
+/// Options of the rich texts extracted from elements (e.g., cells of tables, items of lists) and of the urls
+/// in them. Create them from `TextOptions::default()` and override the fields that need to be changed.
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    // tags that are not kept in the rich text, but their text is
+    pub ignored_tags: Vec<String>,
+    // tags whose text is not extracted
+    pub discard_tags: Vec<String>,
+    // tags kept in the rich text even if they are not inline tags
+    pub keep_tags: Vec<String>,
+    pub only_keep_inline_tags: bool,
+    // attributes containing urls (pairs of tag and attribute) that are resolved to absolute urls
+    pub url_attrs: Vec<(String, String)>,
+    // how urls are canonicalized after they are resolved, e.g., to join links of tables from different pages
+    pub url_canonicalization: URLCanonicalization,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        let to_owned = |tags: &[&str]| tags.iter().map(|tag| (*tag).to_owned()).collect();
+        TextOptions {
+            ignored_tags: to_owned(&["div"]),
+            discard_tags: to_owned(&["script", "style", "noscript", "table"]),
+            keep_tags: to_owned(&["ol", "ul", "li"]),
+            only_keep_inline_tags: true,
+            url_attrs: DEFAULT_URL_ATTRS
+                .iter()
+                .map(|(tag, attr)| ((*tag).to_owned(), (*attr).to_owned()))
+                .collect(),
+            url_canonicalization: URLCanonicalization::default(),
+        }
+    }
+}
+
+/// Extract rich texts of elements and resolve the urls in them following `TextOptions`
+pub struct RichTextExtractor {
+    ignored_tags: HashSet<String>,
+    discard_tags: HashSet<String>,
+    keep_tags: HashSet<String>,
+    options: TextOptions,
+}
+
+impl RichTextExtractor {
+    pub fn new(options: TextOptions) -> Self {
+        RichTextExtractor {
+            ignored_tags: HashSet::from_iter(options.ignored_tags.iter().cloned()),
+            discard_tags: HashSet::from_iter(options.discard_tags.iter().cloned()),
+            keep_tags: HashSet::from_iter(options.keep_tags.iter().cloned()),
+            options,
+        }
+    }
+
+    /// Get rich text of an element
+    pub fn get_rich_text(&self, node: &NodeRef<Node>) -> RichText {
+        get_rich_text(
+            node,
+            &self.ignored_tags,
+            self.options.only_keep_inline_tags,
+            &self.discard_tags,
+            &self.keep_tags,
+        )
+    }
+
+    /// Get the converter that resolves (and canonicalizes) urls of the document
+    pub fn get_url_converter(&self, doc: &Document) -> Result<URLConverter> {
+        Ok(
+            URLConverter::with_url_attrs(doc.base_url(), &self.options.url_attrs)?
+                .with_canonicalization(self.options.url_canonicalization.clone()),
+        )
+    }
+}
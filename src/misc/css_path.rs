@@ -0,0 +1,65 @@
+use ego_tree::NodeRef;
+use scraper::Node;
+
+/// Get a CSS selector that uniquely locates the element in the document,
+/// e.g., `html > body > div:nth-child(2) > table:nth-child(1)`.
+///
+/// The position of an element is its index (1-based) among its element siblings,
+/// so the path does not depend on the text nodes of the document.
+pub fn get_css_path(node: &NodeRef<Node>) -> String {
+    let mut steps = vec![];
+    let mut el = Some(*node);
+
+    while let Some(node) = el {
+        if let Node::Element(node_el) = node.value() {
+            let is_root = node
+                .parent()
+                .is_none_or(|parent| !parent.value().is_element());
+            if is_root {
+                steps.push(node_el.name().to_owned());
+            } else {
+                let nth = node
+                    .prev_siblings()
+                    .filter(|sibling| sibling.value().is_element())
+                    .count()
+                    + 1;
+                steps.push(format!("{}:nth-child({})", node_el.name(), nth));
+            }
+        }
+        el = node.parent();
+    }
+
+    steps.reverse();
+    steps.join(" > ")
+}
+
+/// Get a path of the element that does not depend on the positions of the elements, e.g.,
+/// `html > body > div#content > table.wikitable`. Ancestors are identified by their tag and id
+/// and only the element itself includes its classes, as classes of ancestors (e.g., of `body`) are
+/// often set by scripts or themes. Unlike `get_css_path`, the path does not change when elements
+/// are added to or removed from the document, but it may not be unique.
+pub fn get_structural_path(node: &NodeRef<Node>) -> String {
+    let mut steps = vec![];
+    let mut el = Some(*node);
+
+    while let Some(current) = el {
+        if let Node::Element(node_el) = current.value() {
+            let mut step = node_el.name().to_owned();
+            if let Some(id) = node_el.id() {
+                step.push('#');
+                step.push_str(id);
+            }
+            if current == *node {
+                for class in node_el.classes() {
+                    step.push('.');
+                    step.push_str(class);
+                }
+            }
+            steps.push(step);
+        }
+        el = current.parent();
+    }
+
+    steps.reverse();
+    steps.join(" > ")
+}
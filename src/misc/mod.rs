@@ -1,10 +1,12 @@
-pub mod range_iter;
+pub mod css_path;
 pub mod recursive_iter;
+pub mod source_positions;
 pub mod tree;
 pub mod url_converter;
 
 use hashbrown::HashMap;
 use scraper::node::Attributes;
+use url::Url;
 
 pub fn convert_attrs(attrs: &Attributes) -> HashMap<String, String> {
     attrs
@@ -13,6 +15,18 @@ pub fn convert_attrs(attrs: &Attributes) -> HashMap<String, String> {
         .collect::<HashMap<_, _>>()
 }
 
+/// Create id of an object extracted from a page (e.g., a table or a list) by adding a query parameter identifying
+/// the object (e.g., its order in the page) to the url of the page
+pub fn create_id(url: &Url, param: &str, value: &str) -> String {
+    let mut url = url.clone();
+    let query = match url.query() {
+        None => format!("{}={}", param, value),
+        Some(q) => format!("{}&{}={}", q, param, value),
+    };
+    url.set_query(Some(&query));
+    url.as_str().to_owned()
+}
+
 pub struct ChainN<I, V>
 where
     I: Iterator<Item = V>,
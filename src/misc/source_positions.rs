@@ -0,0 +1,254 @@
+use ego_tree::NodeId;
+use hashbrown::HashMap;
+use html5ever::tendril::{StrTendril, TendrilSink};
+use html5ever::tree_builder::{ElementFlags, NodeOrText, QuirksMode, TreeSink};
+use html5ever::{driver, Attribute, ExpandedName, QualName};
+use scraper::Html;
+use std::borrow::Cow;
+
+/// Position of the start tag of an element in the HTML source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    // line of the start tag (1-based)
+    pub line: usize,
+    // column of the `<` of the start tag in characters (1-based)
+    pub column: usize,
+}
+
+/// Parse a HTML document and record the position of the start tag of each element in the source.
+///
+/// The tokenizer does not report positions of tokens, so the source is fed to the parser in chunks
+/// that start at the `<` of markup (tags, comments, etc.). An element is created when the parser reaches
+/// the end of its start tag, which is in the chunk that starts at the `<` of the tag. Elements implied by
+/// the parser (e.g., `tbody` of rows without a row group) have the position of the tag that creates them.
+///
+/// The chunks are found by `find_chunk_end`, which follows the tokenizer for the parts where `<` does not start
+/// a new tag: quoted attribute values, comments and the text of raw text elements (e.g., `script`). Other
+/// constructs where the tokenizer does not start a tag at `<` (e.g., CDATA sections in SVG) are not followed and
+/// elements created in them may get the position of an earlier `<`.
+pub fn parse_document_with_positions(doc: &str) -> (Html, HashMap<NodeId, SourcePosition>) {
+    let mut parser = driver::parse_document(
+        PositionTrackingSink {
+            html: Html::new_document(),
+            position: SourcePosition { line: 1, column: 1 },
+            positions: HashMap::new(),
+        },
+        Default::default(),
+    );
+
+    // the byte order mark is discarded by the parser and is not counted in the columns
+    let doc = doc.strip_prefix('\u{feff}').unwrap_or(doc);
+    let mut position = SourcePosition { line: 1, column: 1 };
+    let mut chunk_start = 0;
+    while chunk_start < doc.len() {
+        let chunk_end = find_chunk_end(doc, chunk_start);
+        let chunk = &doc[chunk_start..chunk_end];
+
+        parser.tokenizer.sink.sink.position = position;
+        parser.process(StrTendril::from_slice(chunk));
+
+        // move the position to the start of the next chunk
+        match chunk.rfind('\n') {
+            Some(i) => {
+                position.line += chunk.matches('\n').count();
+                position.column = chunk[i + 1..].chars().count() + 1;
+            }
+            None => position.column += chunk.chars().count(),
+        }
+        chunk_start = chunk_end;
+    }
+    parser.finish()
+}
+
+/// Elements whose text is not parsed as markup, `<` in their text does not start a tag
+const RAW_TEXT_TAGS: [&str; 10] = [
+    "script",
+    "style",
+    "textarea",
+    "title",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+];
+
+/// Find the end of the chunk starting at `chunk_start`, which is the next `<` that may start a tag. The markup
+/// starting the chunk is skipped first: the whole tag when the chunk starts with a tag, as quoted attribute values
+/// may contain `<` and `>`, the whole comment, and the text of raw text elements after their start tags.
+fn find_chunk_end(doc: &str, chunk_start: usize) -> usize {
+    let bytes = doc.as_bytes();
+    // the first chunk may not start with `<`
+    let mut end = chunk_start + doc[chunk_start..].chars().next().unwrap().len_utf8();
+    if bytes[chunk_start] == b'<' {
+        let rest = &bytes[chunk_start + 1..];
+        if rest.starts_with(b"!--") {
+            end = doc[chunk_start + 4..]
+                .find("-->")
+                .map_or(doc.len(), |i| chunk_start + 4 + i + 3);
+        } else if rest.first().is_some_and(u8::is_ascii_alphabetic)
+            || (rest.first() == Some(&b'/') && rest.get(1).is_some_and(u8::is_ascii_alphabetic))
+        {
+            end = find_tag_end(bytes, chunk_start);
+            let name_end = rest
+                .iter()
+                .position(|b| b.is_ascii_whitespace() || *b == b'/' || *b == b'>')
+                .unwrap_or(rest.len());
+            let name = &rest[..name_end];
+            if RAW_TEXT_TAGS
+                .iter()
+                .any(|tag| tag.as_bytes().eq_ignore_ascii_case(name))
+            {
+                end = find_end_tag(bytes, end, name);
+            }
+        }
+    }
+    if end >= doc.len() {
+        return doc.len();
+    }
+    doc[end..].find('<').map_or(doc.len(), |i| end + i)
+}
+
+/// Find the end (after the `>`) of the tag starting at `start`. Similar to the tokenizer, a `"` or `'` after
+/// the `=` of an attribute starts a quoted value, which ends at the same quote.
+fn find_tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+    let mut is_after_eq = false;
+    for (i, b) in bytes.iter().enumerate().skip(start + 1) {
+        match quote {
+            Some(q) if *b == q => quote = None,
+            Some(_) => {}
+            None => match b {
+                b'>' => return i + 1,
+                b'"' | b'\'' if is_after_eq => quote = Some(*b),
+                b'=' => {
+                    is_after_eq = true;
+                    continue;
+                }
+                // whitespace between the `=` and the value
+                b if b.is_ascii_whitespace() => continue,
+                _ => {}
+            },
+        }
+        is_after_eq = false;
+    }
+    bytes.len()
+}
+
+/// Find the start of the end tag of a raw text element (case insensitive), searching from `start`
+fn find_end_tag(bytes: &[u8], start: usize, name: &[u8]) -> usize {
+    (start..bytes.len())
+        .find(|&i| {
+            bytes[i] == b'<'
+                && bytes.get(i + 1) == Some(&b'/')
+                && bytes
+                    .get(i + 2..i + 2 + name.len())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(name))
+        })
+        .unwrap_or(bytes.len())
+}
+
+/// A tree sink that builds the same tree as `Html` and records the position of the chunk of the
+/// source being parsed when elements are created
+struct PositionTrackingSink {
+    html: Html,
+    position: SourcePosition,
+    positions: HashMap<NodeId, SourcePosition>,
+}
+
+impl TreeSink for PositionTrackingSink {
+    type Output = (Html, HashMap<NodeId, SourcePosition>);
+    type Handle = NodeId;
+
+    fn finish(self) -> Self::Output {
+        (self.html, self.positions)
+    }
+
+    fn create_element(
+        &mut self,
+        name: QualName,
+        attrs: Vec<Attribute>,
+        flags: ElementFlags,
+    ) -> Self::Handle {
+        let id = self.html.create_element(name, attrs, flags);
+        self.positions.insert(id, self.position);
+        id
+    }
+
+    fn parse_error(&mut self, msg: Cow<'static, str>) {
+        self.html.parse_error(msg)
+    }
+
+    fn get_document(&mut self) -> Self::Handle {
+        self.html.get_document()
+    }
+
+    fn elem_name<'a>(&'a self, target: &'a Self::Handle) -> ExpandedName<'a> {
+        self.html.elem_name(target)
+    }
+
+    fn create_comment(&mut self, text: StrTendril) -> Self::Handle {
+        self.html.create_comment(text)
+    }
+
+    fn create_pi(&mut self, target: StrTendril, data: StrTendril) -> Self::Handle {
+        self.html.create_pi(target, data)
+    }
+
+    fn append(&mut self, parent: &Self::Handle, child: NodeOrText<Self::Handle>) {
+        self.html.append(parent, child)
+    }
+
+    fn append_based_on_parent_node(
+        &mut self,
+        element: &Self::Handle,
+        prev_element: &Self::Handle,
+        child: NodeOrText<Self::Handle>,
+    ) {
+        self.html
+            .append_based_on_parent_node(element, prev_element, child)
+    }
+
+    fn append_doctype_to_document(
+        &mut self,
+        name: StrTendril,
+        public_id: StrTendril,
+        system_id: StrTendril,
+    ) {
+        self.html
+            .append_doctype_to_document(name, public_id, system_id)
+    }
+
+    fn get_template_contents(&mut self, target: &Self::Handle) -> Self::Handle {
+        self.html.get_template_contents(target)
+    }
+
+    fn same_node(&self, x: &Self::Handle, y: &Self::Handle) -> bool {
+        self.html.same_node(x, y)
+    }
+
+    fn set_quirks_mode(&mut self, mode: QuirksMode) {
+        self.html.set_quirks_mode(mode)
+    }
+
+    fn append_before_sibling(
+        &mut self,
+        sibling: &Self::Handle,
+        new_node: NodeOrText<Self::Handle>,
+    ) {
+        self.html.append_before_sibling(sibling, new_node)
+    }
+
+    fn add_attrs_if_missing(&mut self, target: &Self::Handle, attrs: Vec<Attribute>) {
+        self.html.add_attrs_if_missing(target, attrs)
+    }
+
+    fn remove_from_parent(&mut self, target: &Self::Handle) {
+        self.html.remove_from_parent(target)
+    }
+
+    fn reparent_children(&mut self, node: &Self::Handle, new_parent: &Self::Handle) {
+        self.html.reparent_children(node, new_parent)
+    }
+}
@@ -1,54 +1,311 @@
 use anyhow::Result;
+use hashbrown::{HashMap, HashSet};
 use url::Url;
 
-use crate::models::rich_text::RichText;
+use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
+
+/// Default attributes containing urls that are normalized, as pairs of (tag, attribute)
+pub const DEFAULT_URL_ATTRS: [(&str, &str); 13] = [
+    ("a", "href"),
+    ("area", "href"),
+    ("img", "src"),
+    ("img", "srcset"),
+    ("img", "data-src"),
+    ("source", "src"),
+    ("source", "srcset"),
+    ("video", "src"),
+    ("video", "poster"),
+    ("audio", "src"),
+    ("track", "src"),
+    ("iframe", "src"),
+    ("embed", "src"),
+];
+
+/// Tracking query parameters that are removed when canonicalizing urls, names ending with `*` are prefixes
+pub const TRACKING_PARAMS: [&str; 3] = ["utm_*", "fbclid", "gclid"];
+
+/// Options to canonicalize urls so that links to the same resource found in different pages are the same.
+/// All options are disabled by default. Urls are parsed when any option is enabled, which always removes the
+/// default port of the scheme (e.g., `:443` of https).
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, Default)]
+pub struct URLCanonicalization {
+    // lowercase the host, hosts of http(s) urls are always lowercased when they are parsed
+    pub lowercase_host: bool,
+    pub drop_fragment: bool,
+    // remove tracking query parameters (see `TRACKING_PARAMS`)
+    pub remove_tracking_params: bool,
+    // decode percent-encoded unreserved characters (e.g., `%7E` to `~`) and uppercase the remaining escapes
+    pub normalize_percent_encoding: bool,
+    // map mobile wikipedia hosts to desktop hosts, e.g., `en.m.wikipedia.org` to `en.wikipedia.org`
+    pub wikipedia_desktop: bool,
+}
+
+impl URLCanonicalization {
+    /// Whether any option is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.lowercase_host
+            || self.drop_fragment
+            || self.remove_tracking_params
+            || self.normalize_percent_encoding
+            || self.wikipedia_desktop
+    }
+
+    /// Canonicalize the url in place following the enabled options
+    pub fn canonicalize(&self, url: &mut Url) {
+        if self.lowercase_host || self.wikipedia_desktop {
+            if let Some(host) = url.host_str() {
+                let mut new_host = if self.lowercase_host {
+                    host.to_lowercase()
+                } else {
+                    host.to_owned()
+                };
+                if self.wikipedia_desktop {
+                    if let Some(lang) = new_host.strip_suffix(".m.wikipedia.org") {
+                        new_host = format!("{}.wikipedia.org", lang);
+                    }
+                }
+                if new_host != host {
+                    // only fails when the new host is invalid, which is not the case as it is derived from a valid host
+                    let _ = url.set_host(Some(&new_host));
+                }
+            }
+        }
+
+        if self.drop_fragment {
+            url.set_fragment(None);
+        }
+
+        if self.remove_tracking_params {
+            if let Some(query) = url.query() {
+                let params = query
+                    .split('&')
+                    .filter(|param| {
+                        let name = param.split('=').next().unwrap();
+                        !param.is_empty() && !is_tracking_param(name)
+                    })
+                    .collect::<Vec<_>>();
+                if params.is_empty() {
+                    url.set_query(None);
+                } else {
+                    let new_query = params.join("&");
+                    if new_query != query {
+                        url.set_query(Some(&new_query));
+                    }
+                }
+            }
+        }
+
+        if self.normalize_percent_encoding {
+            let path = normalize_percent_encoding(url.path());
+            if path != url.path() {
+                url.set_path(&path);
+            }
+            if let Some(query) = url.query() {
+                let query = normalize_percent_encoding(query);
+                url.set_query(Some(&query));
+            }
+        }
+    }
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    TRACKING_PARAMS
+        .iter()
+        .any(|param| match param.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *param,
+        })
+}
+
+/// Decode percent-encoded unreserved characters (RFC 3986, section 2.3) and uppercase hex digits of other escapes
+fn normalize_percent_encoding(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let byte = u8::from_str_radix(&s[i + 1..i + 3], 16).unwrap();
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                out.push(byte as char);
+            } else {
+                out.push('%');
+                out.push_str(&s[i + 1..i + 3].to_uppercase());
+            }
+            i += 3;
+            continue;
+        }
+        let ch = s[i..].chars().next().unwrap();
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    out
+}
 
 pub struct URLConverter {
     url: Url,
+    // attributes containing urls of each tag
+    url_attrs: HashMap<String, HashSet<String>>,
+    canonicalization: URLCanonicalization,
 }
 
 impl URLConverter {
     pub fn new(raw_url: String) -> Result<Self> {
+        URLConverter::with_url_attrs(raw_url, &DEFAULT_URL_ATTRS)
+    }
+
+    /// Create a converter normalizing the given attributes, as pairs of (tag, attribute). Attributes whose
+    /// names end with `srcset` (e.g., `srcset`, `data-srcset`) are parsed as a list of image candidates.
+    pub fn with_url_attrs<S: AsRef<str>>(raw_url: String, url_attrs: &[(S, S)]) -> Result<Self> {
         let url = Url::parse(&raw_url)?;
-        Ok(URLConverter { url })
+        let mut url_attrs_ = HashMap::<String, HashSet<String>>::new();
+        for (tag, attr) in url_attrs {
+            url_attrs_
+                .entry(tag.as_ref().to_owned())
+                .or_default()
+                .insert(attr.as_ref().to_owned());
+        }
+        Ok(URLConverter {
+            url,
+            url_attrs: url_attrs_,
+            canonicalization: URLCanonicalization::default(),
+        })
+    }
+
+    /// Canonicalize urls after they are resolved
+    pub fn with_canonicalization(mut self, canonicalization: URLCanonicalization) -> Self {
+        self.canonicalization = canonicalization;
+        self
     }
 
+    /// Test if the url is absolute, i.e., it starts with a scheme such as `https:`, `mailto:` or `ftp:`
+    /// (https://url.spec.whatwg.org/#scheme-state).
     #[inline]
     pub fn is_absolute(&self, url: &str) -> bool {
-        // url[..url.len().max(10)].find("://").is_some()
-        url.starts_with("http://") || url.starts_with("https://")
+        let url = url.trim_start_matches(|c: char| c <= ' ');
+        match url.find(':') {
+            None => false,
+            Some(i) => {
+                let scheme = &url[..i];
+                scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+            }
+        }
     }
 
+    /// Resolve the url against the url of the page following the URL standard, e.g., `page.html`,
+    /// `?q=1`, `#frag`, `//host/path` or `/path`.
     #[inline]
     pub fn to_absolute(&self, relative_url: &str) -> Result<String> {
-        if relative_url.starts_with("//") {
-            Ok(format!("{}:{}", self.url.scheme(), relative_url))
-        } else if relative_url.starts_with("/") {
-            Ok(format!(
-                "{}://{}{}",
-                self.url.scheme(),
-                self.url.host_str().unwrap(),
-                relative_url
-            ))
-        } else if relative_url.starts_with(".") {
-            Ok(self.url.join(relative_url)?.as_str().to_owned())
-        } else {
-            Ok(relative_url.to_owned())
+        Ok(self.url.join(relative_url)?.as_str().to_owned())
+    }
+
+    /// Resolve the url if it is relative and canonicalize it if canonicalization is enabled.
+    /// Urls that cannot be resolved are kept as they are.
+    #[inline]
+    pub fn normalize_url(&self, url: &str) -> String {
+        if !self.canonicalization.is_enabled() {
+            if self.is_absolute(url) {
+                return url.to_owned();
+            }
+            return self.to_absolute(url).unwrap_or_else(|_| url.to_owned());
+        }
+
+        match self.url.join(url) {
+            Ok(mut url) => {
+                self.canonicalization.canonicalize(&mut url);
+                url.as_str().to_owned()
+            }
+            Err(_) => url.to_owned(),
         }
     }
 
+    /// Resolve urls of image candidates in a srcset attribute, e.g., `a.png 1x, b.png 2x`, keeping their descriptors
+    /// (https://html.spec.whatwg.org/multipage/images.html#parsing-a-srcset-attribute).
+    pub fn normalize_srcset(&self, srcset: &str) -> String {
+        let mut candidates = vec![];
+        let mut remain = srcset;
+
+        loop {
+            remain = remain.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+            if remain.is_empty() {
+                break;
+            }
+
+            let url_end = remain
+                .find(|c: char| c.is_ascii_whitespace())
+                .unwrap_or(remain.len());
+            let url = &remain[..url_end];
+            remain = &remain[url_end..];
+
+            let (url, descriptors) = if url.ends_with(',') {
+                (url.trim_end_matches(','), "")
+            } else {
+                // descriptors end at the first comma that is not inside parentheses
+                let mut depth = 0;
+                let descriptors_end = remain
+                    .char_indices()
+                    .find(|(_, c)| match c {
+                        '(' => {
+                            depth += 1;
+                            false
+                        }
+                        ')' => {
+                            depth -= 1;
+                            false
+                        }
+                        ',' => depth <= 0,
+                        _ => false,
+                    })
+                    .map_or(remain.len(), |(i, _)| i);
+                let descriptors = remain[..descriptors_end].trim();
+                remain = &remain[descriptors_end..];
+                (url, descriptors)
+            };
+
+            let url = self.normalize_url(url);
+            if descriptors.is_empty() {
+                candidates.push(url);
+            } else {
+                candidates.push(format!("{} {}", url, descriptors));
+            }
+        }
+
+        candidates.join(", ")
+    }
+
     #[inline]
     pub fn normalize_rich_text(&self, rich_text: &mut RichText) {
         for element in rich_text.element.iter_mut() {
-            if element.tag == "a" {
-                if let Some(href) = element.attrs.get("href") {
-                    if !self.is_absolute(href) {
-                        if let Ok(href) = self.to_absolute(href) {
-                            element.attrs.insert("href".to_owned(), href);
-                        }
+            if let Some(url_attrs) = self.url_attrs.get(&element.tag) {
+                for (name, value) in element.attrs.iter_mut() {
+                    if !url_attrs.contains(name) {
+                        continue;
                     }
+                    *value = if name.ends_with("srcset") {
+                        self.normalize_srcset(value)
+                    } else {
+                        self.normalize_url(value)
+                    };
                 }
             }
         }
     }
+
+    /// Normalize urls in the heading and the content of a level of context
+    pub fn normalize_content_hierarchy(&self, content: &mut ContentHierarchy) {
+        self.normalize_rich_text(&mut content.heading);
+        for line in &mut content.content_before {
+            self.normalize_rich_text(line);
+        }
+        for line in &mut content.content_after {
+            self.normalize_rich_text(line);
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
+
+/// Key-value pairs found in a page, e.g., in a definition list (`dl`) or an infobox
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KeyValueRecord {
+    pub id: String,
+    pub url: String,
+    // the element containing the pairs: `dl` or `table` (infobox)
+    pub tag: String,
+    // caption of the infobox or its first row if it is a single header cell, empty for definition lists
+    pub title: String,
+    pub attrs: HashMap<String, String>,
+    pub context: Vec<ContentHierarchy>,
+    pub pairs: Vec<KeyValuePair>,
+}
+
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KeyValuePair {
+    pub key: RichText,
+    pub value: RichText,
+}
+
+impl KeyValueRecord {
+    /// Get text of the keys and values
+    pub fn to_list(&self) -> Vec<(String, String)> {
+        self.pairs
+            .iter()
+            .map(|pair| (pair.key.text.clone(), pair.value.text.clone()))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(dat: &str) -> Result<KeyValueRecord> {
+        Ok(serde_json::from_str(dat)?)
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
+
+/// A list (`ul` or `ol`) of the page and its nested lists
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct List {
+    pub id: String,
+    pub url: String,
+    // type of the list: `ul` or `ol`
+    pub tag: String,
+    pub attrs: HashMap<String, String>,
+    pub context: Vec<ContentHierarchy>,
+    pub items: Vec<ListItem>,
+}
+
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListItem {
+    // content of the item without its nested lists
+    pub value: RichText,
+    // type of the list containing the item: `ul` or `ol`
+    pub list_type: String,
+    // depth of the list containing the item, items of the top list have depth 0
+    pub depth: usize,
+    // items of the lists nested in this item
+    pub children: Vec<ListItem>,
+}
+
+impl List {
+    /// Get the items and their nested items in document order (pre-order)
+    pub fn flatten(&self) -> Vec<&ListItem> {
+        let mut items = vec![];
+        let mut stack = self.items.iter().rev().collect::<Vec<_>>();
+        while let Some(item) = stack.pop() {
+            items.push(item);
+            stack.extend(item.children.iter().rev());
+        }
+        items
+    }
+
+    /// Get depth and text of the items in document order
+    pub fn to_list(&self) -> Vec<(usize, String)> {
+        self.flatten()
+            .into_iter()
+            .map(|item| (item.depth, item.value.text.clone()))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(dat: &str) -> Result<List> {
+        Ok(serde_json::from_str(dat)?)
+    }
+}
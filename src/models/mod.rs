@@ -1,3 +1,5 @@
 pub mod content_hierarchy;
+pub mod key_value;
+pub mod list;
 pub mod rich_text;
 pub mod table;
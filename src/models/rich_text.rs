@@ -1,21 +1,19 @@
+use anyhow::{bail, Result};
 use hashbrown::HashMap;
-use pyo3::exceptions::PyKeyError;
-use pyo3::types::PyBytes;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
 
 use crate::misc::tree::iterator::ITree;
 use crate::misc::tree::simple_tree::SimpleTree;
-use crate::{error::into_pyerr, misc::range_iter::RangeIter};
-use postcard::{from_bytes, to_allocvec};
-use pyo3::{prelude::*, types::PyDict, types::PyList};
-use serde::{Deserialize, Serialize};
 
 pub const PSEUDO_TAG: &str = "";
 
-#[pyclass(module = "rsoup.core")]
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
 #[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RichText {
-    #[pyo3(get)]
     pub text: String,
     // html elements creating this text, the root of the tree
     // is a pseudo-element, most often, it will be the html element containing
@@ -24,17 +22,35 @@ pub struct RichText {
     pub element: SimpleTree<RichTextElement>,
 }
 
+/// Unit of offsets into the text of a rich text. Offsets of elements are stored in bytes (UTF-8),
+/// while python slices strings by characters (code points) and javascript by UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetUnit {
+    Byte,
+    Char,
+    Utf16,
+}
+
+impl FromStr for OffsetUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "byte" => Ok(OffsetUnit::Byte),
+            "char" => Ok(OffsetUnit::Char),
+            "utf16" => Ok(OffsetUnit::Utf16),
+            _ => bail!("Invalid offset unit: {}", s),
+        }
+    }
+}
+
 /// Represent an html element.
-#[pyclass(module = "rsoup.core")]
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RichTextElement {
-    #[pyo3(get)]
     pub tag: String,
-    #[pyo3(get)]
     pub start: usize,
-    #[pyo3(get)]
     pub end: usize,
-    #[pyo3(get)]
     pub attrs: HashMap<String, String>,
 }
 
@@ -81,11 +97,7 @@ impl RichText {
         }
         is_valid
     }
-}
 
-#[pymethods]
-impl RichText {
-    #[staticmethod]
     pub fn from_str(text: &str) -> RichText {
         RichText {
             text: text.to_owned(),
@@ -102,18 +114,319 @@ impl RichText {
         self.text.len()
     }
 
-    pub fn iter_element_id(&self) -> RangeIter {
-        RangeIter {
+    /// Length of the text in the given unit
+    pub fn len_in(&self, unit: OffsetUnit) -> usize {
+        match unit {
+            OffsetUnit::Byte => self.text.len(),
+            OffsetUnit::Char => self.text.chars().count(),
+            OffsetUnit::Utf16 => self.text.encode_utf16().count(),
+        }
+    }
+
+    /// Convert an offset into the text from one unit to another. Return None if the offset is out of
+    /// the text or is not at the boundary of a character (e.g., inside a multi-byte character or
+    /// between the two code units of a surrogate pair).
+    pub fn convert_offset(&self, offset: usize, from: OffsetUnit, to: OffsetUnit) -> Option<usize> {
+        let byte_offset = match from {
+            OffsetUnit::Byte => {
+                if !self.text.is_char_boundary(offset) {
+                    return None;
+                }
+                offset
+            }
+            OffsetUnit::Char => {
+                if offset == 0 {
+                    0
+                } else {
+                    let (i, c) = self.text.char_indices().nth(offset - 1)?;
+                    i + c.len_utf8()
+                }
+            }
+            OffsetUnit::Utf16 => {
+                let mut utf16_offset = 0;
+                let mut byte_offset = 0;
+                for c in self.text.chars() {
+                    if utf16_offset >= offset {
+                        break;
+                    }
+                    utf16_offset += c.len_utf16();
+                    byte_offset += c.len_utf8();
+                }
+                if utf16_offset != offset {
+                    return None;
+                }
+                byte_offset
+            }
+        };
+
+        Some(match to {
+            OffsetUnit::Byte => byte_offset,
+            OffsetUnit::Char => self.text[..byte_offset].chars().count(),
+            OffsetUnit::Utf16 => self.text[..byte_offset].encode_utf16().count(),
+        })
+    }
+
+    /// Get a part of the rich text between the byte offsets. Elements that do not overlap with the range are removed,
+    /// except empty elements (e.g., images) inside the range, and the others are clipped. Ids of the remaining elements
+    /// are reassigned in pre-order.
+    ///
+    /// Empty elements at the edges of the range belong to the text before them, so each of them is only kept in one of
+    /// adjacent slices: the elements at the end of the range are kept, the elements at the start of the range are only
+    /// kept when the range starts at the beginning of the text.
+    pub fn slice(&self, start: usize, end: usize) -> Result<RichText> {
+        self.check_range(start, end)?;
+        Ok(self.slice_unchecked(start, end, start == 0, true))
+    }
+
+    /// Same as `slice`, but whether empty elements at the start and the end of the range are kept is given
+    fn slice_unchecked(
+        &self,
+        start: usize,
+        end: usize,
+        keep_start: bool,
+        keep_end: bool,
+    ) -> RichText {
+        let root = self.element.get_root();
+        let mut element = SimpleTree::new(RichTextElement {
+            tag: root.tag.clone(),
             start: 0,
-            end: self.element.len(),
+            end: end - start,
+            attrs: root.attrs.clone(),
+        });
+        let mut stack = self
+            .element
+            .get_child_ids(self.element.get_root_id())
+            .iter()
+            .rev()
+            .map(|child_id| (*child_id, 0))
+            .collect::<Vec<_>>();
+        while let Some((old_id, parent_id)) = stack.pop() {
+            let node = self.element.get_node(old_id);
+            let is_kept = if node.start == node.end {
+                (start < node.start && node.start < end)
+                    || (node.start == start && keep_start)
+                    || (node.start == end && keep_end)
+            } else {
+                node.start < end && node.end > start
+            };
+            if !is_kept {
+                continue;
+            }
+            let new_id = element.add_node(RichTextElement {
+                tag: node.tag.clone(),
+                start: node.start.clamp(start, end) - start,
+                end: node.end.clamp(start, end) - start,
+                attrs: node.attrs.clone(),
+            });
+            element.add_child(parent_id, new_id);
+            stack.extend(
+                self.element
+                    .get_child_ids(old_id)
+                    .iter()
+                    .rev()
+                    .map(|child_id| (*child_id, new_id)),
+            );
+        }
+
+        RichText {
+            text: self.text[start..end].to_owned(),
+            element,
         }
     }
 
-    pub fn iter_element_id_preorder(
-        slf: Py<RichText>,
-        py: Python,
-    ) -> RichTextElementIdPreorderIter {
-        RichTextElementIdPreorderIter::new(slf.clone_ref(py))
+    /// Concatenate rich texts with a separator. The root of the result is a pseudo-element containing the
+    /// roots of the rich texts, or their children if the roots are pseudo-elements.
+    pub fn concat(texts: &[RichText], separator: &str) -> RichText {
+        let mut text = String::new();
+        let mut element = SimpleTree::new(RichTextElement {
+            tag: PSEUDO_TAG.to_owned(),
+            start: 0,
+            end: 0,
+            attrs: HashMap::new(),
+        });
+        let root_id = element.get_root_id();
+
+        for (i, rich_text) in texts.iter().enumerate() {
+            if i > 0 {
+                text.push_str(separator);
+            }
+            let offset = text.len();
+            let mut subtree = rich_text.element.clone();
+            for node in subtree.iter_mut() {
+                node.start += offset;
+                node.end += offset;
+            }
+            if rich_text.get_tag() == PSEUDO_TAG {
+                element.merge_subtree_no_root(root_id, subtree);
+            } else {
+                element.merge_subtree(root_id, subtree);
+            }
+            text.push_str(&rich_text.text);
+        }
+        element.get_root_mut().end = text.len();
+
+        RichText { text, element }
+    }
+
+    /// Replace the text between the byte offsets. Elements containing the range are resized, elements after the range
+    /// are shifted, and elements partially overlapping the range are clipped so that they do not cover the replacement.
+    pub fn replace_range(&self, start: usize, end: usize, replacement: &str) -> Result<RichText> {
+        self.check_range(start, end)?;
+        let mut rich_text = self.clone();
+        rich_text.replace_range_unchecked(start, end, replacement);
+        Ok(rich_text)
+    }
+
+    /// Replace every match of the regex with the replacement, which can refer to the capture groups (e.g., `$1`
+    /// or `${name}`). Elements are adjusted the same way as `replace_range`.
+    pub fn substitute(&self, pattern: &str, replacement: &str) -> Result<RichText> {
+        let re = Regex::new(pattern)?;
+        let replacements = re
+            .captures_iter(&self.text)
+            .map(|caps| {
+                let m = caps.get(0).unwrap();
+                let mut dst = String::new();
+                caps.expand(replacement, &mut dst);
+                (m.start(), m.end(), dst)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rich_text = self.clone();
+        // replace from the end so that the offsets of the remaining matches are unchanged
+        for (start, end, dst) in replacements.into_iter().rev() {
+            rich_text.replace_range_unchecked(start, end, &dst);
+        }
+        Ok(rich_text)
+    }
+
+    /// Remove leading and trailing whitespaces
+    pub fn strip(&self) -> RichText {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice_unchecked(start, end, true, true)
+    }
+
+    /// Split the rich text by a (non-empty) separator. Empty elements at the edges of a separator are kept in the
+    /// part next to them, i.e., the part before the separator if they are at its start, the part after it otherwise.
+    pub fn split(&self, separator: &str) -> Result<Vec<RichText>> {
+        if separator.is_empty() {
+            bail!("Empty separator");
+        }
+        let mut parts = vec![];
+        let mut start = 0;
+        for (i, _) in self.text.match_indices(separator) {
+            parts.push(self.slice_unchecked(start, i, true, true));
+            start = i + separator.len();
+        }
+        parts.push(self.slice_unchecked(start, self.text.len(), true, true));
+        Ok(parts)
+    }
+
+    fn check_range(&self, start: usize, end: usize) -> Result<()> {
+        if start > end
+            || end > self.text.len()
+            || !self.text.is_char_boundary(start)
+            || !self.text.is_char_boundary(end)
+        {
+            bail!(
+                "Invalid range [{}, {}) of text of {} bytes",
+                start,
+                end,
+                self.text.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn replace_range_unchecked(&mut self, start: usize, end: usize, replacement: &str) {
+        let new_end = start + replacement.len();
+        for node in self.element.iter_mut() {
+            let node_end = if node.end <= start {
+                node.end
+            } else if node.end < end {
+                start
+            } else {
+                node.end - end + new_end
+            };
+            let node_start = if node.start <= start {
+                node.start
+            } else if node.start < end {
+                new_end
+            } else {
+                node.start - end + new_end
+            };
+            node.start = node_start.min(node_end);
+            node.end = node_end;
+        }
+        self.text.replace_range(start..end, replacement);
+        self.clip_element_ranges();
+    }
+
+    /// Clip ranges of the elements so that they are inside their parents and after their previous siblings
+    fn clip_element_ranges(&mut self) {
+        let root_id = self.element.get_root_id();
+        let root = self.element.get_root_mut();
+        root.start = 0;
+        root.end = self.text.len();
+
+        let mut stack = vec![root_id];
+        while let Some(node_id) = stack.pop() {
+            let node = self.element.get_node(node_id);
+            let (mut prev_end, parent_end) = (node.start, node.end);
+            let child_ids = self.element.get_child_ids(node_id).to_vec();
+            for child_id in &child_ids {
+                let child = self.element.get_node_mut(*child_id);
+                child.start = child.start.clamp(prev_end, parent_end);
+                child.end = child.end.clamp(child.start, parent_end);
+                prev_end = child.end;
+            }
+            stack.extend(child_ids);
+        }
+    }
+
+    /// Get the range (start, end) of an element in the given unit
+    pub fn get_element_range(&self, id: usize, unit: OffsetUnit) -> (usize, usize) {
+        let element = self.element.get_node(id);
+        match unit {
+            OffsetUnit::Byte => (element.start, element.end),
+            _ => (
+                self.convert_offset(element.start, OffsetUnit::Byte, unit)
+                    .unwrap(),
+                self.convert_offset(element.end, OffsetUnit::Byte, unit)
+                    .unwrap(),
+            ),
+        }
+    }
+
+    /// Get the ranges of all elements (ordered by their ids) in the given unit
+    pub fn get_element_ranges(&self, unit: OffsetUnit) -> Vec<(usize, usize)> {
+        if unit == OffsetUnit::Byte {
+            return (0..self.element.len())
+                .map(|id| {
+                    let element = self.element.get_node(id);
+                    (element.start, element.end)
+                })
+                .collect();
+        }
+
+        // offset in the unit at each char boundary of the text, indexed by bytes
+        let mut offsets = vec![0; self.text.len() + 1];
+        let mut offset = 0;
+        for (i, c) in self.text.char_indices() {
+            offsets[i] = offset;
+            offset += match unit {
+                OffsetUnit::Utf16 => c.len_utf16(),
+                _ => 1,
+            };
+        }
+        offsets[self.text.len()] = offset;
+
+        (0..self.element.len())
+            .map(|id| {
+                let element = self.element.get_node(id);
+                (offsets[element.start], offsets[element.end])
+            })
+            .collect()
     }
 
     pub fn get_element_tag_by_id(&self, id: usize) -> String {
@@ -143,15 +456,54 @@ impl RichText {
             .map(ToOwned::to_owned)
     }
 
-    #[args("*", render_outer_element = "true", render_element_attrs = "false")]
     pub fn to_html(&self, render_outer_element: bool, render_element_attrs: bool) -> String {
-        let mut tokens = Vec::<&str>::with_capacity(2 + self.element.len());
+        self.write_html(
+            render_outer_element,
+            |text, out| out.push_str(text),
+            |element, out| {
+                if render_element_attrs {
+                    for (name, value) in element.attrs.iter() {
+                        out.push(' ');
+                        out.push_str(name);
+                        out.push_str("=\"");
+                        out.push_str(&value.replace('"', "\\\""));
+                        out.push('"');
+                    }
+                }
+            },
+        )
+    }
+
+    /// Render the content of the rich text (without the outer element) to valid HTML: `&`, `<` and `>` in the text
+    /// and `&` and `"` in attribute values are escaped, and only attributes accepted by `keep_attr` are rendered.
+    pub(crate) fn to_escaped_html(
+        &self,
+        keep_attr: impl Fn(&RichTextElement, &str) -> bool,
+    ) -> String {
+        self.write_html(false, escape_html, |element, out| {
+            for (name, value) in element.attrs.iter() {
+                if keep_attr(element, name) {
+                    out.push(' ');
+                    out.push_str(name);
+                    out.push_str("=\"");
+                    escape_html_attr(value, out);
+                    out.push('"');
+                }
+            }
+        })
+    }
+
+    fn write_html(
+        &self,
+        render_outer_element: bool,
+        write_text: impl Fn(&str, &mut String),
+        write_attrs: impl Fn(&RichTextElement, &mut String),
+    ) -> String {
+        let mut out = String::with_capacity(self.text.len());
         // keep track of pending tags that need to be closed
         let mut closing_tag_ids = Vec::<usize>::new();
         let mut pointer = 0;
         let mut it = self.element.iter_id_preorder();
-        let mut string_pools = Vec::new();
-        let mut pending_ops = Vec::new();
 
         if !render_outer_element {
             it.next();
@@ -160,8 +512,8 @@ impl RichText {
         for token_id in it {
             let token = self.element.get_node(*token_id);
             // println!(
-            //     "------before\n\t>> pointer: {}\n\t>> token: {:?}\n\t>> tokens: {:?}\n\t>> closing_tags: {:?}",
-            //     pointer, token, tokens, closing_tag_ids.iter().map(|id| self.element.get_node(*id)).collect::<Vec<_>>()
+            //     "------before\n\t>> pointer: {}\n\t>> token: {:?}\n\t>> out: {:?}\n\t>> closing_tags: {:?}",
+            //     pointer, token, out, closing_tag_ids.iter().map(|id| self.element.get_node(*id)).collect::<Vec<_>>()
             // );
 
             while let Some(closing_tag_id) = closing_tag_ids.last() {
@@ -183,10 +535,10 @@ impl RichText {
                             break;
                         }
                     }
-                    tokens.push(&self.text[pointer..closing_tag.end]);
-                    tokens.push("</");
-                    tokens.push(&closing_tag.tag);
-                    tokens.push(">");
+                    write_text(&self.text[pointer..closing_tag.end], &mut out);
+                    out.push_str("</");
+                    out.push_str(&closing_tag.tag);
+                    out.push('>');
                     pointer = closing_tag.end;
                     closing_tag_ids.pop();
                 } else {
@@ -194,231 +546,301 @@ impl RichText {
                 }
             }
 
-            tokens.push(&self.text[pointer..token.start]);
-            tokens.push("<");
-            tokens.push(&token.tag);
-            if render_element_attrs {
-                for (name, value) in token.attrs.iter() {
-                    tokens.push(" ");
-                    tokens.push(name);
-                    tokens.push("=\"");
-                    string_pools.push(value.replace("\"", "\\\""));
-                    pending_ops.push(tokens.len());
-                    tokens.push("");
-                    tokens.push("\"");
-                }
-            }
-            tokens.push(">");
+            write_text(&self.text[pointer..token.start], &mut out);
+            out.push('<');
+            out.push_str(&token.tag);
+            write_attrs(token, &mut out);
+            out.push('>');
 
             pointer = token.start;
             closing_tag_ids.push(*token_id);
 
             // println!(
-            //     "------after\n\t>> pointer: {}\n\t>> token: {:?}\n\t>> tokens: {:?}\n\t>> closing_tags: {:?}",
-            //     pointer, token, tokens, closing_tag_ids.iter().map(|id| self.element.get_node(*id)).collect::<Vec<_>>()
+            //     "------after\n\t>> pointer: {}\n\t>> token: {:?}\n\t>> out: {:?}\n\t>> closing_tags: {:?}",
+            //     pointer, token, out, closing_tag_ids.iter().map(|id| self.element.get_node(*id)).collect::<Vec<_>>()
             // );
         }
 
         for closing_tag_id in closing_tag_ids.iter().rev() {
             let closing_tag = self.element.get_node(*closing_tag_id);
-            tokens.push(&self.text[pointer..closing_tag.end]);
-            tokens.push("</");
-            tokens.push(&closing_tag.tag);
-            tokens.push(">");
+            write_text(&self.text[pointer..closing_tag.end], &mut out);
+            out.push_str("</");
+            out.push_str(&closing_tag.tag);
+            out.push('>');
             pointer = closing_tag.end;
         }
-        tokens.push(&self.text[pointer..]);
+        write_text(&self.text[pointer..], &mut out);
 
-        // update tokens
-        for (i, j) in pending_ops.into_iter().enumerate() {
-            tokens[j] = &string_pools[i];
-        }
+        out
+    }
 
-        tokens.join("")
-    }
-
-    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let tree = PyDict::new(py);
-
-        tree.set_item("root", self.element.get_root_id())?;
-        tree.set_item(
-            "nodes",
-            self.element
-                .iter()
-                .iter()
-                .map(|u| u.to_dict(py))
-                .collect::<PyResult<Vec<_>>>()?,
-        )?;
-        tree.set_item("node2children", &self.element.node2children)?;
-
-        let d = PyDict::new(py);
-        d.set_item("text", &self.text)?;
-        d.set_item("element", tree)?;
-        Ok(d.into_py(py))
-    }
-
-    #[staticmethod]
-    pub fn from_dict(obj: &PyDict) -> PyResult<Self> {
-        let text = obj
-            .get_item("text")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("text"))?
-            .extract::<String>()?;
-
-        let elem_obj = obj
-            .get_item("element")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("element"))?
-            .downcast::<PyDict>()?;
-        let root = elem_obj
-            .get_item("root")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("root in element"))?
-            .extract::<usize>()?;
-        let nodes = elem_obj
-            .get_item("nodes")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("nodes in element"))?
-            .downcast::<PyList>()?
-            .iter()
-            .map(|o| RichTextElement::from_dict(o.downcast::<PyDict>()?))
-            .collect::<PyResult<Vec<_>>>()?;
-        let node2children = elem_obj
-            .get_item("node2children")
-            .ok_or_else(|| {
-                PyErr::new::<pyo3::exceptions::PyKeyError, _>("node2children in element")
-            })?
-            .extract::<Vec<Vec<usize>>>()?;
-
-        Ok(RichText {
-            text,
-            element: SimpleTree::from_data(root, nodes, node2children),
-        })
+    /// Render the rich text to Markdown. Links, images, emphasis (`b`, `strong`, `i`, `em`), `code` and headings are
+    /// mapped to their Markdown syntax, `sup` and `sub` are rendered as inline HTML, lists (`ul`, `ol` and `li`, which
+    /// need to be in `keep_tags` when extracting the rich text) are rendered as Markdown lists. Other elements only
+    /// render their content, and special characters in the text are escaped.
+    pub fn to_markdown(&self) -> String {
+        self.render_markdown(self.element.get_root_id(), "- ", false)
     }
 
-    #[new]
-    pub fn new() -> Self {
-        RichText::empty()
+    /// Render the rich text to Markdown to be put in a cell of a Markdown table, which has to fit in one line: line
+    /// breaks are rendered as `<br>` and `|` in code spans is escaped as tables are split into cells before code spans
+    /// are parsed.
+    pub(crate) fn to_markdown_table_cell(&self) -> String {
+        self.render_markdown(self.element.get_root_id(), "- ", true)
+            .replace('\n', "<br>")
     }
 
-    pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
-        // Implementing pickling support according to this issue: https://github.com/PyO3/pyo3/issues/100
-        let out = to_allocvec(&self).map_err(into_pyerr)?;
-        Ok(PyBytes::new(py, &out))
+    /// Render an element to Markdown, `marker` is the list marker used if the element is a list item and `in_table`
+    /// tells whether the element is rendered in a cell of a Markdown table
+    fn render_markdown(&self, node_id: usize, marker: &str, in_table: bool) -> String {
+        let node = self.element.get_node(node_id);
+        if node.tag == "code" {
+            // the fence is one backtick longer than the longest run of backticks in the code, spaces are added when the
+            // code starts or ends with a backtick or a space as parsers strip one space at both ends
+            let mut code = Cow::Borrowed(&self.text[node.start..node.end]);
+            if in_table {
+                // line breaks in code spans are rendered as spaces, `<br>` would be kept literally
+                code = Cow::Owned(code.replace('\n', " ").replace('|', "\\|"));
+            }
+            let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest_run + 1);
+            let padding = if code.starts_with(['`', ' ']) || code.ends_with(['`', ' ']) {
+                " "
+            } else {
+                ""
+            };
+            return format!("{}{}{}{}{}", fence, padding, code, padding, fence);
+        }
+
+        let mut content = String::new();
+        let mut pointer = node.start;
+        let mut item_no = if node.tag == "ol" {
+            node.attrs
+                .get("start")
+                .and_then(|start| start.trim().parse::<usize>().ok())
+                .unwrap_or(1)
+        } else {
+            0
+        };
+        for child_id in self.element.get_child_ids(node_id) {
+            let child = self.element.get_node(*child_id);
+            escape_markdown(&self.text, pointer, child.start, &mut content);
+            if child.tag == "li" && node.tag == "ol" {
+                content.push_str(&self.render_markdown(
+                    *child_id,
+                    &format!("{}. ", item_no),
+                    in_table,
+                ));
+                item_no += 1;
+            } else {
+                content.push_str(&self.render_markdown(*child_id, "- ", in_table));
+            }
+            pointer = child.end;
+        }
+        escape_markdown(&self.text, pointer, node.end, &mut content);
+
+        match node.tag.as_str() {
+            "a" => match node.attrs.get("href") {
+                Some(href) if !content.is_empty() => {
+                    format!("[{}]({})", content, escape_markdown_url(href))
+                }
+                _ => content,
+            },
+            "img" => match node.attrs.get("src") {
+                Some(src) => {
+                    let mut alt = String::new();
+                    if let Some(alt_) = node.attrs.get("alt") {
+                        escape_markdown(alt_, 0, alt_.len(), &mut alt);
+                    }
+                    format!("![{}]({})", alt, escape_markdown_url(src))
+                }
+                None => content,
+            },
+            "b" | "strong" => wrap_markdown(&content, "**"),
+            "i" | "em" => wrap_markdown(&content, "*"),
+            "sup" | "sub" => format!("<{}>{}</{}>", node.tag, content, node.tag),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = node.tag[1..].parse::<usize>().unwrap();
+                format!("{} {}", "#".repeat(level), content.trim())
+            }
+            "li" => {
+                // lines after the first one are indented to be inside the item
+                let indent = " ".repeat(marker.len());
+                let mut item = marker.to_owned();
+                for (i, line) in content.trim().split('\n').enumerate() {
+                    if i > 0 {
+                        item.push('\n');
+                        if !line.is_empty() {
+                            item.push_str(&indent);
+                        }
+                    }
+                    item.push_str(line);
+                }
+                item
+            }
+            _ => content,
+        }
     }
 
-    pub fn __setstate__(&mut self, state: &PyBytes) -> PyResult<()> {
-        *self = from_bytes::<RichText>(state.as_bytes()).map_err(into_pyerr)?;
-        Ok(())
+    /// Render the rich text to LaTeX. Links, emphasis (`b`, `strong`, `i`, `em`), `code`, `sup` and `sub` are mapped
+    /// to their LaTeX commands (links need the `hyperref` package), other elements only render their content, and
+    /// special characters in the text are escaped.
+    pub fn to_latex(&self) -> String {
+        self.render_latex(self.element.get_root_id())
     }
-}
 
-#[pymethods]
-impl RichTextElement {
-    fn get_attr(&self, name: &str) -> PyResult<&String> {
-        self.attrs
-            .get(name)
-            .ok_or_else(|| PyKeyError::new_err(format!("{name} not found")))
-    }
-
-    fn has_attr(&self, name: &str) -> PyResult<bool> {
-        Ok(self.attrs.contains_key(name))
-    }
-
-    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let d = PyDict::new(py);
-        d.set_item("tag", &self.tag)?;
-        d.set_item("start", self.start)?;
-        d.set_item("end", self.end)?;
-        d.set_item("attrs", &self.attrs)?;
-        Ok(d.into_py(py))
-    }
-
-    #[staticmethod]
-    fn from_dict(obj: &PyDict) -> PyResult<RichTextElement> {
-        let tag = obj
-            .get_item("tag")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("tag"))?
-            .extract::<String>()?;
-        let start = obj
-            .get_item("start")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("start"))?
-            .extract::<usize>()?;
-        let end = obj
-            .get_item("end")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("end"))?
-            .extract::<usize>()?;
-        let attrs = obj
-            .get_item("attrs")
-            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("attrs"))?
-            .extract::<HashMap<String, String>>()?;
-        Ok(RichTextElement {
-            tag,
-            start,
-            end,
-            attrs,
-        })
+    fn render_latex(&self, node_id: usize) -> String {
+        let node = self.element.get_node(node_id);
+        let mut content = String::new();
+        let mut pointer = node.start;
+        for child_id in self.element.get_child_ids(node_id) {
+            let child = self.element.get_node(*child_id);
+            escape_latex(&self.text[pointer..child.start], &mut content);
+            content.push_str(&self.render_latex(*child_id));
+            pointer = child.end;
+        }
+        escape_latex(&self.text[pointer..node.end], &mut content);
+
+        let command = match node.tag.as_str() {
+            "a" => match node.attrs.get("href") {
+                Some(href) if !content.is_empty() => {
+                    let mut url = String::new();
+                    for c in href.chars() {
+                        if matches!(c, '%' | '#' | '&' | '\\' | '{' | '}') {
+                            url.push('\\');
+                        }
+                        url.push(c);
+                    }
+                    return format!("\\href{{{}}}{{{}}}", url, content);
+                }
+                _ => return content,
+            },
+            "b" | "strong" => "textbf",
+            "i" | "em" => "textit",
+            "code" => "texttt",
+            "sup" => "textsuperscript",
+            "sub" => "textsubscript",
+            _ => return content,
+        };
+        if content.is_empty() {
+            return content;
+        }
+        format!("\\{}{{{}}}", command, content)
     }
 }
 
-impl fmt::Display for RichText {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "`{}`", self.to_html(false, false))
+/// Escape HTML special characters of a text node and write the text to the output
+pub(crate) fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
     }
 }
 
-impl fmt::Debug for RichText {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "`{}`", self.to_html(true, false))
+/// Escape HTML special characters of a double-quoted attribute value and write the value to the output
+fn escape_html_attr(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
     }
 }
 
-#[pyclass(module = "rsoup.core")]
-pub struct RichTextElementIdPreorderIter {
-    text: Py<RichText>,
-    stack: Vec<(usize, usize)>,
-    inited: bool,
+/// Escape LaTeX special characters and write the text to the output
+pub(crate) fn escape_latex(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ => out.push(c),
+        }
+    }
 }
 
-impl RichTextElementIdPreorderIter {
-    pub fn new(text: Py<RichText>) -> Self {
-        RichTextElementIdPreorderIter {
-            text,
-            stack: Vec::new(),
-            inited: false,
+/// Escape Markdown special characters of the text between the byte offsets and write it to the output. Characters
+/// that are only special at the beginning of a line (e.g., `#`, `-` or `1.`) are escaped there.
+fn escape_markdown(text: &str, start: usize, end: usize, out: &mut String) {
+    let mut is_line_start = start == 0 || text[..start].ends_with('\n');
+    let segment = &text[start..end];
+    for (i, c) in segment.char_indices() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '#' | '-' | '+' | '=' if is_line_start => {
+                out.push('\\');
+                out.push(c);
+            }
+            '.' | ')'
+                if is_line_start
+                    && segment[..i]
+                        .rsplit('\n')
+                        .next()
+                        .map(str::trim_start)
+                        .is_some_and(|s| {
+                            !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+                        }) =>
+            {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+
+        if c == '\n' {
+            is_line_start = true;
+        } else if !c.is_whitespace() && !c.is_ascii_digit() {
+            is_line_start = false;
         }
     }
 }
 
-#[pymethods]
-impl RichTextElementIdPreorderIter {
-    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
-        slf
-    }
+/// Escape characters of an url that would end the link destination, or the cell when the link is in a table
+fn escape_markdown_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+        .replace('|', "%7C")
+}
 
-    fn __next__(&mut self, py: Python) -> Option<usize> {
-        let text = self.text.borrow(py);
-        loop {
-            if self.stack.len() == 0 {
-                if self.inited {
-                    return None;
-                }
-                self.inited = true;
-                self.stack.push((text.element.get_root_id(), 0));
-                return Some(self.stack[self.stack.len() - 1].0);
-            }
+/// Wrap the content by the emphasis marker, leading and trailing whitespaces are kept outside of the markers
+fn wrap_markdown(content: &str, marker: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_owned();
+    }
+    let start = content.len() - content.trim_start().len();
+    let end = start + trimmed.len();
+    format!(
+        "{}{}{}{}{}",
+        &content[..start],
+        marker,
+        trimmed,
+        marker,
+        &content[end..]
+    )
+}
 
-            // current element has been returned previously
-            // so we will try to return its child
-            let n1 = self.stack.len() - 1;
-            let (node, child_index) = self.stack[n1];
-            let node_children = text.element.get_child_ids(node);
-
-            if child_index < node_children.len() {
-                // add this child to stack
-                self.stack.push((node_children[child_index], 0));
-                self.stack[n1].1 += 1;
-                return Some(node_children[child_index]);
-            }
+impl fmt::Display for RichText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`", self.to_html(false, false))
+    }
+}
 
-            // no child to return, done at this level, so we move up
-            self.stack.pop();
-        }
+impl fmt::Debug for RichText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`", self.to_html(true, false))
     }
 }
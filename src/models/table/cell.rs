@@ -1,71 +1,96 @@
 use hashbrown::HashMap;
-use pyo3::{prelude::*, types::PyDict};
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
+use super::SourceLocation;
 use crate::models::rich_text::RichText;
 
-#[pyclass(module = "rsoup.core")]
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Cell {
-    #[pyo3(get, set)]
     pub is_header: bool,
-    #[pyo3(get, set)]
     pub rowspan: u16,
-    #[pyo3(get, set)]
     pub colspan: u16,
-    #[pyo3(get)]
     pub attrs: HashMap<String, String>,
     // include the outer tags of the cell
-    #[pyo3(get, set)]
-    pub value: Py<RichText>,
+    pub value: RichText,
+    // ids of tables nested directly in this cell, only available when
+    // nested tables are extracted
+    #[serde(default)]
+    pub nested_table_ids: Vec<String>,
+    // whether the cell overlaps with other cells when the table is spanned
+    #[serde(default)]
+    pub span_conflict: bool,
+    // position of the cell in the original table, only available after the table is spanned
+    #[serde(default)]
+    pub origin: Option<CellOrigin>,
+    // location of the td/th element, only available when locations are recorded during extraction
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+}
+
+/// Position of a cell in the original (not spanned) table and the area the cell covers in the spanned table
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CellOrigin {
+    // index of the row containing the cell in the original table
+    pub row: usize,
+    // index of the cell in its row in the original table
+    pub cell: usize,
+    // the covered area in the spanned table, bottom and right are exclusive
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
 }
 
-#[pymethods]
 impl Cell {
-    #[new]
-    pub fn new(
-        is_header: bool,
-        rowspan: u16,
-        colspan: u16,
-        attrs: HashMap<String, String>,
-        value: Py<RichText>,
-    ) -> Self {
+    /// Create a non-header cell without attributes and span
+    pub fn from_value(value: RichText) -> Self {
         Cell {
-            is_header,
-            rowspan,
-            colspan,
-            attrs,
+            is_header: false,
+            rowspan: 1,
+            colspan: 1,
+            attrs: HashMap::new(),
             value,
+            nested_table_ids: Vec::new(),
+            span_conflict: false,
+            origin: None,
+            location: None,
         }
     }
 
-    fn __str__(&self, py: Python) -> String {
-        self.value.borrow(py).to_html(true, false)
+    /// Whether the cell at the given position of the spanned table is a copy of a merged cell
+    /// and not the top-left cell of the merged area.
+    pub fn is_span_copy(&self, ri: usize, ci: usize) -> bool {
+        self.origin
+            .as_ref()
+            .is_some_and(|origin| origin.top != ri || origin.left != ci)
     }
+}
 
-    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let o = PyDict::new(py);
+impl CellOrigin {
+    pub fn new(
+        row: usize,
+        cell: usize,
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+    ) -> Self {
+        CellOrigin {
+            row,
+            cell,
+            top,
+            left,
+            bottom,
+            right,
+        }
+    }
 
-        o.set_item("is_header", &self.is_header)?;
-        o.set_item("rowspan", self.rowspan)?;
-        o.set_item("colspan", self.colspan)?;
-        o.set_item("attrs", &self.attrs)?;
-        o.set_item("value", self.value.borrow(py).to_dict(py)?)?;
-        Ok(o.into_py(py))
+    pub fn rowspan(&self) -> usize {
+        self.bottom - self.top
     }
-}
 
-impl fmt::Debug for Cell {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Python::with_gil(|py| {
-            f.debug_struct("Cell")
-                .field("is_header", &self.is_header)
-                .field("rowspan", &self.rowspan)
-                .field("colspan", &self.colspan)
-                .field("attrs", &self.attrs)
-                .field("value", &self.value.borrow(py))
-                .finish()
-        })
+    pub fn colspan(&self) -> usize {
+        self.right - self.left
     }
 }
@@ -0,0 +1,126 @@
+use hashbrown::HashMap;
+
+use super::header::is_numeric;
+use super::Table;
+
+/// Features of a table used to tell apart data tables from layout tables (page layouts, navigation bars, etc.)
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone)]
+pub struct TableFeatures {
+    // the table has role="presentation" or role="none"
+    pub is_presentation: bool,
+    // the table has th cells, thead rows or a caption
+    pub has_header_markup: bool,
+    pub n_rows: usize,
+    pub n_cols: usize,
+    // number of cells that are not empty
+    pub n_non_empty_cells: usize,
+    // average length of the text of non-empty cells
+    pub avg_text_len: f64,
+    // fraction of non-empty cells containing block content (multiple lines or nested tables)
+    pub block_content_ratio: f64,
+    // average over columns of the fraction of cells having the most common type (number or text)
+    pub column_type_uniformity: f64,
+}
+
+impl TableFeatures {
+    /// Score how likely the table is a data table, from 0 (layout table) to 1 (data table)
+    pub fn score(&self) -> f64 {
+        if self.is_presentation {
+            return 0.0;
+        }
+
+        let mut score = 0.5;
+        if self.has_header_markup {
+            score += 0.2;
+        }
+        if self.n_rows < 2 || self.n_cols < 2 {
+            score -= 0.3;
+        }
+        if self.n_non_empty_cells < 4 {
+            score -= 0.2;
+        }
+        if self.avg_text_len > 300.0 {
+            score -= 0.3;
+        } else if self.avg_text_len > 100.0 {
+            score -= 0.2;
+        }
+        score -= 0.3 * self.block_content_ratio;
+        score += 0.3 * (self.column_type_uniformity - 0.5);
+
+        score.clamp(0.0, 1.0)
+    }
+}
+
+/// Compute features of a table to classify whether it is a data table or a layout table.
+/// The table should be spanned so that cells of the same column are aligned.
+pub fn get_table_features(table: &Table) -> TableFeatures {
+    let rows = &table.rows;
+
+    let is_presentation = table
+        .attrs
+        .get("role")
+        .is_some_and(|role| matches!(role.trim(), "presentation" | "none"));
+    let mut has_header_markup = !table.caption.is_empty();
+
+    let n_cols = rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+    let mut n_non_empty_cells = 0;
+    let mut n_block_cells = 0;
+    let mut total_text_len = 0;
+    // count of (column, is number) of cells in the body (the first row is skipped as it is often the header)
+    let mut column_types = HashMap::<(usize, bool), usize>::new();
+
+    for (ri, row) in rows.iter().enumerate() {
+        has_header_markup = has_header_markup || row.section == "thead";
+        for (ci, cell) in row.cells.iter().enumerate() {
+            has_header_markup = has_header_markup || cell.is_header;
+
+            let value = &cell.value;
+            if value.text.is_empty() {
+                continue;
+            }
+            n_non_empty_cells += 1;
+            total_text_len += value.text.chars().count();
+            if value.text.contains('\n') || !cell.nested_table_ids.is_empty() {
+                n_block_cells += 1;
+            }
+            if ri > 0 {
+                *column_types
+                    .entry((ci, is_numeric(&value.text)))
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut uniformities = vec![];
+    for ci in 0..n_cols {
+        let n_numbers = column_types.get(&(ci, true)).copied().unwrap_or(0);
+        let n_texts = column_types.get(&(ci, false)).copied().unwrap_or(0);
+        if n_numbers + n_texts > 0 {
+            uniformities.push(n_numbers.max(n_texts) as f64 / (n_numbers + n_texts) as f64);
+        }
+    }
+
+    TableFeatures {
+        is_presentation,
+        has_header_markup,
+        n_rows: rows.len(),
+        n_cols,
+        n_non_empty_cells,
+        avg_text_len: if n_non_empty_cells > 0 {
+            total_text_len as f64 / n_non_empty_cells as f64
+        } else {
+            0.0
+        },
+        block_content_ratio: if n_non_empty_cells > 0 {
+            n_block_cells as f64 / n_non_empty_cells as f64
+        } else {
+            0.0
+        },
+        column_type_uniformity: if uniformities.is_empty() {
+            0.5
+        } else {
+            uniformities.iter().sum::<f64>() / uniformities.len() as f64
+        },
+    }
+}
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use csv::{QuoteStyle, ReaderBuilder, WriterBuilder};
+use hashbrown::HashMap;
+
+use super::render::get_spanned_table;
+use super::{Cell, Row, Table};
+use crate::models::rich_text::RichText;
+
+/// When fields are quoted in CSV files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CSVQuoting {
+    /// only fields containing the delimiter, quotes or line breaks
+    #[default]
+    Necessary,
+    Always,
+    /// fields that are not numbers
+    NonNumeric,
+    /// never quote fields, the output may not be parsed back if fields contain special characters
+    Never,
+}
+
+impl FromStr for CSVQuoting {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "necessary" => Ok(CSVQuoting::Necessary),
+            "always" => Ok(CSVQuoting::Always),
+            "non_numeric" => Ok(CSVQuoting::NonNumeric),
+            "never" => Ok(CSVQuoting::Never),
+            _ => bail!("Invalid CSV quoting: {}", s),
+        }
+    }
+}
+
+/// Options to write tables to and read tables from CSV (or TSV) files
+#[derive(Debug, Clone)]
+pub struct CSVOptions {
+    // e.g., `b','` for CSV and `b'\t'` for TSV
+    pub delimiter: u8,
+    pub quoting: CSVQuoting,
+    // when writing, header rows are merged into one row of column names (e.g., `Population / 2010`), otherwise,
+    // all rows are written as they are. Tables without header rows are written with a row of empty column names.
+    // When reading, the first rows are header cells, and a first row of empty column names is skipped.
+    pub header: bool,
+    // number of header rows, detected when writing and 1 when reading if not provided
+    pub n_header_rows: Option<usize>,
+    // when writing, add a column containing links (separated by spaces) of the cells after each column having links
+    pub link_columns: bool,
+}
+
+impl Default for CSVOptions {
+    fn default() -> Self {
+        CSVOptions {
+            delimiter: b',',
+            quoting: CSVQuoting::Necessary,
+            header: true,
+            n_header_rows: None,
+            link_columns: false,
+        }
+    }
+}
+
+/// Write a table to CSV. The table is spanned if it is not, and the text of the cells is written.
+pub fn to_csv(table: &Table, options: &CSVOptions) -> Result<String> {
+    let table = get_spanned_table(table);
+    let ncols = table.rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote_style(match options.quoting {
+            CSVQuoting::Necessary => QuoteStyle::Necessary,
+            CSVQuoting::Always => QuoteStyle::Always,
+            CSVQuoting::NonNumeric => QuoteStyle::NonNumeric,
+            CSVQuoting::Never => QuoteStyle::Never,
+        })
+        .flexible(true)
+        .from_writer(vec![]);
+
+    let n_header_rows = if options.header {
+        options
+            .n_header_rows
+            .unwrap_or_else(|| table.detect_header().0)
+            .min(table.rows.len())
+    } else {
+        0
+    };
+    // tables without header rows are written with empty column names, so that the first row of the body is not
+    // read back as the column names
+    let (header, body) = if !options.header {
+        (None, &table.rows[..])
+    } else if n_header_rows > 0 {
+        (
+            Some(table.get_column_names(Some(n_header_rows), " / ")),
+            &table.rows[n_header_rows..],
+        )
+    } else {
+        (Some(vec![String::new(); ncols]), &table.rows[..])
+    };
+
+    let has_links = (0..ncols)
+        .map(|ci| {
+            options.link_columns
+                && body.iter().any(|row| {
+                    row.cells
+                        .get(ci)
+                        .is_some_and(|cell| !get_links(cell).is_empty())
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(header) = header {
+        let mut record = vec![];
+        for (ci, name) in header.into_iter().enumerate() {
+            if has_links[ci] {
+                record.push(name.clone());
+                record.push(format!("{} (links)", name));
+            } else {
+                record.push(name);
+            }
+        }
+        writer.write_record(&record)?;
+    }
+
+    for row in body {
+        let mut record = vec![];
+        for (ci, cell) in row.cells.iter().enumerate() {
+            record.push(cell.value.text.clone());
+            if has_links[ci] {
+                record.push(get_links(cell).join(" "));
+            }
+        }
+        writer.write_record(&record)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Read a table from CSV, rows may have different numbers of cells. Header rows are put in thead, except a first
+/// row of empty column names, which is written by `to_csv` for tables without header rows and is skipped.
+pub fn from_csv(id: String, url: String, dat: &str, options: &CSVOptions) -> Result<Table> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .quoting(options.quoting != CSVQuoting::Never)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(dat.as_bytes());
+
+    let mut n_header_rows = if options.header {
+        options.n_header_rows.unwrap_or(1)
+    } else {
+        0
+    };
+    let mut records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    if n_header_rows > 0
+        && records
+            .first()
+            .is_some_and(|record| record.iter().all(str::is_empty))
+    {
+        records.remove(0);
+        n_header_rows -= 1;
+    }
+
+    let mut rows = vec![];
+    for (ri, record) in records.into_iter().enumerate() {
+        let is_header = ri < n_header_rows;
+        let cells = record
+            .iter()
+            .map(|value| {
+                let mut cell = Cell::from_value(RichText::from_str(value));
+                cell.is_header = is_header;
+                cell
+            })
+            .collect();
+        let section = if is_header { "thead" } else { "tbody" };
+        let mut row = Row::new(cells, HashMap::new(), section.to_owned());
+        row.row_group = if is_header { 0 } else { 1 };
+        rows.push(row);
+    }
+
+    Ok(Table {
+        id,
+        url,
+        caption: String::new(),
+        attrs: HashMap::new(),
+        context: Vec::new(),
+        rows,
+        location: None,
+    })
+}
+
+fn get_links(cell: &Cell) -> Vec<&str> {
+    cell.value
+        .element
+        .iter()
+        .iter()
+        .filter(|element| element.tag == "a")
+        .filter_map(|element| element.attrs.get("href").map(String::as_str))
+        .collect()
+}
@@ -0,0 +1,16 @@
+/// A table that is found in the page but is not returned by the extractor
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone)]
+pub struct DiscardedTable {
+    // index of the table element among the table elements of the page targeted by the
+    // include/exclude selectors of the extractor (document order)
+    pub table_index: usize,
+    // css selector locating the table element in the page
+    pub path: String,
+    // why the table is discarded: `empty` (no rows or cells), `nested` (contains other tables and
+    // nested tables are not extracted), `overlap_span`, `invalid_cell_span` or `layout` (data table
+    // score is below the threshold)
+    pub reason: String,
+    // position of the cell causing the span error: (row index, cell index, column index)
+    pub span_position: Option<(usize, usize, usize)>,
+}
@@ -0,0 +1,199 @@
+use super::{Cell, Row, Table};
+use crate::models::rich_text::RichText;
+
+/// Tags that make a cell looks like a header when they wrap the whole text of the cell
+const HEADER_STYLING_TAGS: [&str; 2] = ["b", "strong"];
+
+/// Infer the number of header rows and header columns of a table. The table is expected
+/// to be spanned so that cells of the same column are aligned.
+///
+/// A row (column) is a header row (column) when all of its non-empty cells look like headers: they are
+/// th cells, cells in thead, cells with scope col/colgroup (row/rowgroup for columns), or cells whose text is
+/// wrapped entirely by a bold tag. When no header row is found by the markup, the first row is a header
+/// row if it has no numeric cell while some columns of the remaining rows are mostly numeric.
+///
+/// At least one row (column) is always left as the body.
+pub fn detect_header(table: &Table) -> (usize, usize) {
+    let rows = &table.rows;
+    if rows.len() < 2 {
+        return (0, 0);
+    }
+
+    let mut n_header_rows = 0;
+    while n_header_rows < rows.len() - 1
+        && is_header_line(rows[n_header_rows].cells.iter(), |cell| {
+            is_header_cell(&rows[n_header_rows], cell, ["col", "colgroup"])
+        })
+    {
+        n_header_rows += 1;
+    }
+
+    if n_header_rows == 0 && is_header_row_by_type(rows) {
+        n_header_rows = 1;
+    }
+
+    let ncols = rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+    let body = &rows[n_header_rows..];
+    let mut n_header_cols = 0;
+    while n_header_cols + 1 < ncols
+        && is_header_line(
+            body.iter().filter_map(|r| r.cells.get(n_header_cols)),
+            |cell| {
+                cell.is_header
+                    || has_scope(cell, ["row", "rowgroup"])
+                    || is_styled_as_header(&cell.value)
+            },
+        )
+    {
+        n_header_cols += 1;
+    }
+
+    (n_header_rows, n_header_cols)
+}
+
+/// Merge the header rows of a spanned table into a path of headers for each column, from the top header row
+/// to the bottom one.
+///
+/// Header cells are merged vertically when they are copies of the same cell (e.g., a header with rowspan),
+/// or when the table is not spanned, when they have the same text. Empty headers are skipped.
+///
+/// # Arguments
+///
+/// * `n_header_rows` - number of header rows, detected automatically if not provided
+pub fn get_column_header_paths(table: &Table, n_header_rows: Option<usize>) -> Vec<Vec<String>> {
+    get_column_header_paths_by(table, n_header_rows, |cell| cell.value.text.clone())
+}
+
+/// Same as `get_column_header_paths` but headers are rendered by `render_cell` (e.g., to Markdown) instead
+/// of using the text of the cells
+pub(super) fn get_column_header_paths_by<F>(
+    table: &Table,
+    n_header_rows: Option<usize>,
+    render_cell: F,
+) -> Vec<Vec<String>>
+where
+    F: Fn(&Cell) -> String,
+{
+    let n_header_rows = n_header_rows.unwrap_or_else(|| detect_header(table).0);
+    let rows = &table.rows;
+    let ncols = rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+
+    (0..ncols)
+        .map(|ci| {
+            let mut path: Vec<String> = vec![];
+            let mut prev_cell: Option<&Cell> = None;
+
+            for row in rows.iter().take(n_header_rows) {
+                let cell = match row.cells.get(ci) {
+                    None => continue,
+                    Some(cell) => cell,
+                };
+                let text = render_cell(cell);
+                if text.is_empty() {
+                    continue;
+                }
+
+                let is_same_cell = match &prev_cell {
+                    None => false,
+                    Some(prev_cell) => match (&prev_cell.origin, &cell.origin) {
+                        (Some(prev_origin), Some(origin)) => prev_origin == origin,
+                        _ => path.last() == Some(&text),
+                    },
+                };
+                if !is_same_cell {
+                    path.push(text);
+                }
+                prev_cell = Some(cell);
+            }
+            path
+        })
+        .collect()
+}
+
+/// Test if all non-empty cells of a row (column) are headers, and there is at least one non-empty cell
+fn is_header_line<'a, I, F>(cells: I, is_header: F) -> bool
+where
+    I: Iterator<Item = &'a Cell>,
+    F: Fn(&Cell) -> bool,
+{
+    let mut has_content = false;
+    for cell in cells {
+        if cell.value.text.is_empty() {
+            continue;
+        }
+        if !is_header(cell) {
+            return false;
+        }
+        has_content = true;
+    }
+    has_content
+}
+
+fn is_header_cell<const N: usize>(row: &Row, cell: &Cell, scopes: [&str; N]) -> bool {
+    cell.is_header
+        || row.section == "thead"
+        || has_scope(cell, scopes)
+        || is_styled_as_header(&cell.value)
+}
+
+fn has_scope<const N: usize>(cell: &Cell, scopes: [&str; N]) -> bool {
+    cell.attrs
+        .get("scope")
+        .is_some_and(|scope| scopes.contains(&scope.trim().to_lowercase().as_str()))
+}
+
+/// Test if the whole text is wrapped by a styling tag such as `<b>`
+fn is_styled_as_header(text: &RichText) -> bool {
+    if text.text.is_empty() {
+        return false;
+    }
+    let root_id = text.element.get_root_id();
+    text.element.iter_id_preorder().any(|node_id| {
+        let node = text.element.get_node(*node_id);
+        *node_id != root_id
+            && HEADER_STYLING_TAGS.contains(&node.tag.as_str())
+            && node.start == 0
+            && node.end == text.text.len()
+    })
+}
+
+/// Test if the first row is a header because its cells are not numbers while the cells in the
+/// same columns of the following rows are mostly numbers
+fn is_header_row_by_type(rows: &[Row]) -> bool {
+    let first_row = rows[0]
+        .cells
+        .iter()
+        .map(|c| c.value.text.clone())
+        .collect::<Vec<_>>();
+    if first_row.iter().all(|text| text.is_empty()) || first_row.iter().any(|text| is_numeric(text))
+    {
+        return false;
+    }
+
+    (0..first_row.len()).any(|ci| {
+        let mut n_values = 0;
+        let mut n_numbers = 0;
+        for row in &rows[1..] {
+            if let Some(cell) = row.cells.get(ci) {
+                let value = &cell.value;
+                if value.text.is_empty() {
+                    continue;
+                }
+                n_values += 1;
+                if is_numeric(&value.text) {
+                    n_numbers += 1;
+                }
+            }
+        }
+        !first_row[ci].is_empty() && n_values > 0 && n_numbers * 2 > n_values
+    })
+}
+
+/// Test if the text is a number such as `1,234`, `-3.5` or `12%`, words that parse to non-finite
+/// numbers (e.g., `inf`, `NaN`, `Infinity`) are not numbers
+pub(super) fn is_numeric(text: &str) -> bool {
+    let text = text.trim();
+    let text = text.strip_suffix('%').unwrap_or(text);
+    let text = text.replace(',', "");
+    text.parse::<f64>().is_ok_and(f64::is_finite)
+}
@@ -0,0 +1,69 @@
+use hashbrown::HashMap;
+use serde::Deserialize;
+
+use super::{Cell, Row, Table};
+use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
+
+/// Layout of tables in the binary format written by versions before the format had a version marker,
+/// i.e., before rows and cells recorded their section, span and location. Postcard encodes fields by
+/// their position, so these tables cannot be decoded with the current structs.
+#[derive(Deserialize)]
+pub(super) struct LegacyTable {
+    id: String,
+    url: String,
+    caption: String,
+    attrs: HashMap<String, String>,
+    context: Vec<ContentHierarchy>,
+    rows: Vec<LegacyRow>,
+}
+
+#[derive(Deserialize)]
+struct LegacyRow {
+    cells: Vec<LegacyCell>,
+    attrs: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct LegacyCell {
+    is_header: bool,
+    rowspan: u16,
+    colspan: u16,
+    attrs: HashMap<String, String>,
+    value: RichText,
+}
+
+impl From<LegacyTable> for Table {
+    fn from(table: LegacyTable) -> Self {
+        Table {
+            id: table.id,
+            url: table.url,
+            caption: table.caption,
+            attrs: table.attrs,
+            context: table.context,
+            rows: table.rows.into_iter().map(Row::from).collect(),
+            location: None,
+        }
+    }
+}
+
+impl From<LegacyRow> for Row {
+    fn from(row: LegacyRow) -> Self {
+        Row::new(
+            row.cells.into_iter().map(Cell::from).collect(),
+            row.attrs,
+            "tbody".to_owned(),
+        )
+    }
+}
+
+impl From<LegacyCell> for Cell {
+    fn from(cell: LegacyCell) -> Self {
+        Cell {
+            is_header: cell.is_header,
+            rowspan: cell.rowspan,
+            colspan: cell.colspan,
+            attrs: cell.attrs,
+            ..Cell::from_value(cell.value)
+        }
+    }
+}
@@ -1,9 +1,18 @@
 pub mod cell;
-pub mod cell_iter;
+pub mod classifier;
+pub mod csv;
+pub mod discarded_table;
+pub mod header;
+mod legacy;
+pub mod render;
 pub mod row;
-pub mod row_iter;
+pub mod source_location;
 pub mod table;
 
-pub use self::cell::Cell;
+pub use self::cell::{Cell, CellOrigin};
+pub use self::classifier::TableFeatures;
+pub use self::csv::{CSVOptions, CSVQuoting};
+pub use self::discarded_table::DiscardedTable;
 pub use self::row::Row;
+pub use self::source_location::SourceLocation;
 pub use self::table::Table;
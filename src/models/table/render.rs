@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+
+use super::{header::get_column_header_paths_by, Cell, Table};
+use crate::models::rich_text::{escape_html, escape_latex};
+
+/// Render a table to a (GitHub flavored) Markdown table. Markdown tables cannot merge cells, so the table is spanned
+/// if it is not and values of merged cells are repeated. Markdown tables have exactly one header row, multiple header
+/// rows are merged into one (e.g., `Population / 2010`) and an empty header row is added if the table has no header.
+///
+/// # Arguments
+///
+/// * `n_header_rows` - number of header rows, detected automatically if not provided
+pub fn to_markdown(table: &Table, n_header_rows: Option<usize>) -> String {
+    let table = get_spanned_table(table);
+    let ncols = table.rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+    if ncols == 0 {
+        return String::new();
+    }
+    let n_header_rows = n_header_rows
+        .unwrap_or_else(|| table.detect_header().0)
+        .min(table.rows.len());
+    let render_cell = |cell: &Cell| cell.value.to_markdown_table_cell();
+
+    let header = get_column_header_paths_by(&table, Some(n_header_rows), render_cell)
+        .into_iter()
+        .map(|path| path.join(" / "))
+        .collect::<Vec<_>>();
+
+    let mut lines = vec![
+        markdown_row(header.iter().map(String::as_str), ncols),
+        markdown_row(std::iter::repeat_n("---", ncols), ncols),
+    ];
+    for row in &table.rows[n_header_rows..] {
+        let cells = row.cells.iter().map(render_cell).collect::<Vec<_>>();
+        lines.push(markdown_row(cells.iter().map(String::as_str), ncols));
+    }
+    lines.join("\n")
+}
+
+/// Attributes of the elements in cells that are kept when rendering tables to HTML, as pairs of (tag, attribute).
+/// Other attributes (e.g., `class`, `style` or `id`) only have a meaning in the page the table is extracted from.
+const HTML_CELL_ATTRS: [(&str, &str); 5] = [
+    ("a", "href"),
+    ("area", "href"),
+    ("img", "src"),
+    ("img", "alt"),
+    ("abbr", "title"),
+];
+
+/// Render a table to a clean HTML table containing only the structure (caption, sections, header and data cells) and
+/// the content of the cells, in which only the attributes in `HTML_CELL_ATTRS` are kept. Merged cells of a spanned
+/// table are collapsed back into cells with rowspan and colspan, otherwise, rowspan and colspan of the cells are kept
+/// as they are.
+pub fn to_html(table: &Table) -> String {
+    let mut out = String::from("<table>\n");
+    if !table.caption.is_empty() {
+        out.push_str("<caption>");
+        escape_html(&table.caption, &mut out);
+        out.push_str("</caption>\n");
+    }
+
+    let mut section: Option<&str> = None;
+    for (ri, row) in table.rows.iter().enumerate() {
+        let row_section = match row.section.as_str() {
+            "thead" | "tfoot" => row.section.as_str(),
+            _ => "tbody",
+        };
+        if section != Some(row_section) {
+            if let Some(section) = section {
+                out.push_str(&format!("</{}>\n", section));
+            }
+            out.push_str(&format!("<{}>\n", row_section));
+            section = Some(row_section);
+        }
+
+        out.push_str("<tr>");
+        for (ci, cell) in row.cells.iter().enumerate() {
+            if cell.is_span_copy(ri, ci) {
+                continue;
+            }
+            let (rowspan, colspan) = match &cell.origin {
+                Some(origin) => (origin.rowspan(), origin.colspan()),
+                None => (cell.rowspan as usize, cell.colspan as usize),
+            };
+            let tag = if cell.is_header { "th" } else { "td" };
+            out.push('<');
+            out.push_str(tag);
+            if rowspan != 1 {
+                out.push_str(&format!(" rowspan=\"{}\"", rowspan));
+            }
+            if colspan != 1 {
+                out.push_str(&format!(" colspan=\"{}\"", colspan));
+            }
+            out.push('>');
+            out.push_str(&cell.value.to_escaped_html(|element, attr| {
+                HTML_CELL_ATTRS.contains(&(element.tag.as_str(), attr))
+            }));
+            out.push_str(&format!("</{}>", tag));
+        }
+        out.push_str("</tr>\n");
+    }
+    if let Some(section) = section {
+        out.push_str(&format!("</{}>\n", section));
+    }
+    out.push_str("</table>");
+    out
+}
+
+/// Render a table to a LaTeX tabular, wrapped in a table environment if the table has a caption. The table is spanned
+/// if it is not, and merged cells are rendered with `\multicolumn` and `\multirow` (needs the `multirow` package).
+/// Header rows are separated from the body by a horizontal line.
+///
+/// # Arguments
+///
+/// * `n_header_rows` - number of header rows, detected automatically if not provided
+pub fn to_latex(table: &Table, n_header_rows: Option<usize>) -> String {
+    let table = get_spanned_table(table);
+    let ncols = table.rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+    let n_header_rows = n_header_rows.unwrap_or_else(|| table.detect_header().0);
+
+    let mut out = String::new();
+    if !table.caption.is_empty() {
+        out.push_str("\\begin{table}\n\\caption{");
+        escape_latex(&table.caption, &mut out);
+        out.push_str("}\n");
+    }
+    out.push_str(&format!(
+        "\\begin{{tabular}}{{{}}}\n\\hline\n",
+        "l".repeat(ncols)
+    ));
+
+    for (ri, row) in table.rows.iter().enumerate() {
+        let mut cells = vec![];
+        let mut ci = 0;
+        while ci < row.cells.len() {
+            let cell = &row.cells[ci];
+            let (top, left, rowspan, colspan) = match &cell.origin {
+                Some(origin) => (origin.top, origin.left, origin.rowspan(), origin.colspan()),
+                None => (ri, ci, 1, 1),
+            };
+            if left != ci {
+                // covered by the multicolumn of the previous cell
+                ci += 1;
+                continue;
+            }
+            let colspan = colspan.clamp(1, row.cells.len() - ci);
+
+            // copies of the cell in the rows below are left empty
+            let mut value = String::new();
+            if top == ri {
+                value = cell.value.to_latex();
+                if rowspan > 1 {
+                    value = format!("\\multirow{{{}}}{{*}}{{{}}}", rowspan, value);
+                }
+            }
+            if colspan > 1 {
+                value = format!("\\multicolumn{{{}}}{{l}}{{{}}}", colspan, value);
+            }
+            cells.push(value);
+            ci += colspan;
+        }
+        out.push_str(&cells.join(" & "));
+        out.push_str(" \\\\\n");
+        if ri + 1 == n_header_rows {
+            out.push_str("\\hline\n");
+        }
+    }
+
+    out.push_str("\\hline\n\\end{tabular}");
+    if !table.caption.is_empty() {
+        out.push_str("\n\\end{table}");
+    }
+    out
+}
+
+/// Get the spanned version of the table, tables that cannot be spanned are spanned following the HTML specification
+pub(super) fn get_spanned_table(table: &Table) -> Cow<'_, Table> {
+    let is_spanned = table
+        .rows
+        .iter()
+        .any(|row| row.cells.iter().any(|cell| cell.origin.is_some()));
+    if is_spanned {
+        Cow::Borrowed(table)
+    } else {
+        Cow::Owned(table.span().unwrap_or_else(|_| table.span_html_spec()))
+    }
+}
+
+fn markdown_row<'s>(cells: impl Iterator<Item = &'s str>, ncols: usize) -> String {
+    let mut cells = cells.collect::<Vec<_>>();
+    cells.resize(ncols, "");
+    format!("| {} |", cells.join(" | "))
+}
@@ -1,78 +1,45 @@
 use hashbrown::HashMap;
-use pyo3::{exceptions::PyKeyError, prelude::*, types::PyDict};
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-use super::Cell;
+use super::{Cell, SourceLocation};
 
-#[derive(Clone, Deserialize, Serialize)]
-#[pyclass(module = "rsoup.core")]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Row {
-    #[pyo3(get)]
-    pub cells: Vec<Py<Cell>>,
-    #[pyo3(get)]
+    pub cells: Vec<Cell>,
     pub attrs: HashMap<String, String>,
+    // the row group (thead, tbody or tfoot) containing this row, rows that are
+    // direct children of the table belong to an implicit tbody
+    #[serde(default = "default_section")]
+    pub section: String,
+    // index of the row group element containing this row in the table, consecutive rows that are direct
+    // children of the table share one group. Rowspan of cells does not go past their row group.
+    #[serde(default)]
+    pub row_group: usize,
+    // location of the tr element, only available when locations are recorded during extraction
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
 }
 
-#[pymethods]
-impl Row {
-    #[new]
-    pub fn new(cells: Vec<Py<Cell>>, attrs: HashMap<String, String>) -> Self {
-        Row { cells, attrs }
-    }
-
-    fn get_cell(&self, py: Python, ci: usize) -> PyResult<Py<Cell>> {
-        if ci >= self.cells.len() {
-            return Err(PyKeyError::new_err(format!(
-                "Key {} is out of cells' range [0, {})",
-                ci,
-                self.cells.len()
-            )));
-        }
-
-        Ok(self.cells[ci].clone_ref(py))
-    }
+fn default_section() -> String {
+    "tbody".to_owned()
+}
 
-    fn iter_cells(slf: Py<Row>, py: Python) -> super::cell_iter::CellRIter {
-        super::cell_iter::CellRIter {
-            row: slf.clone_ref(py),
-            cell_index: 0,
+impl Row {
+    pub fn new(cells: Vec<Cell>, attrs: HashMap<String, String>, section: String) -> Self {
+        Row {
+            cells,
+            attrs,
+            section,
+            row_group: 0,
+            location: None,
         }
     }
 
-    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let o = PyDict::new(py);
-
-        o.set_item("attrs", &self.attrs)?;
-        o.set_item(
-            "cells",
-            &self
-                .cells
-                .iter()
-                .map(|c| c.borrow(py).to_dict(py))
-                .collect::<PyResult<Vec<_>>>()?,
-        )?;
-        Ok(o.into_py(py))
-    }
-
-    pub(super) fn to_list(&self, py: Python) -> Vec<String> {
-        self.cells
-            .iter()
-            .map(|c| c.borrow(py).value.borrow(py).text.clone())
-            .collect()
+    pub fn get_cell(&self, ci: usize) -> Option<&Cell> {
+        self.cells.get(ci)
     }
-}
 
-impl fmt::Debug for Row {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Python::with_gil(|py| {
-            f.debug_struct("Row")
-                .field(
-                    "cells",
-                    &self.cells.iter().map(|l| l.borrow(py)).collect::<Vec<_>>(),
-                )
-                .field("attrs", &self.attrs)
-                .finish()
-        })
+    pub fn to_list(&self) -> Vec<String> {
+        self.cells.iter().map(|c| c.value.text.clone()).collect()
     }
 }
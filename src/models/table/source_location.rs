@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Location of the element of a table, row or cell in the HTML document
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "rsoup.core"))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SourceLocation {
+    // css selector locating the element in the document (see `misc::css_path::get_css_path`)
+    pub css_path: String,
+    // line (1-based) of the start tag of the element in the HTML source, not available
+    // when the document is not parsed from a source (e.g., built from a fragment)
+    pub line: Option<usize>,
+    // column (1-based, in characters) of the `<` of the start tag in its line, available with the line
+    pub column: Option<usize>,
+}
+
+impl SourceLocation {
+    pub fn new(css_path: String, line: Option<usize>, column: Option<usize>) -> Self {
+        SourceLocation {
+            css_path,
+            line,
+            column,
+        }
+    }
+}
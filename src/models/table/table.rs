@@ -1,67 +1,39 @@
 use anyhow::Result;
-use hashbrown::HashMap;
-use pyo3::{
-    exceptions::PyKeyError,
-    prelude::*,
-    types::{PyBytes, PyDict, PyString},
-};
+use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use serde_json;
-use std::fmt;
 
-use super::{Cell, Row};
+use super::classifier::get_table_features;
+use super::legacy::LegacyTable;
+use super::{CSVOptions, Cell, CellOrigin, Row, SourceLocation, TableFeatures};
+use crate::error::RSoupError;
 use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
 
-#[derive(Clone, Deserialize, Serialize)]
-#[pyclass(module = "rsoup.core")]
+/// Magic bytes starting tables in the binary format (see `Table::to_bytes`)
+pub const BINARY_MAGIC: &[u8; 4] = b"\xffRST";
+/// Version of the binary format, increased when the fields of tables, rows or cells change. Version 1 is the
+/// format without the magic bytes and the version, written before rows and cells had their section, span and location.
+pub const BINARY_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Table {
-    #[pyo3(get, set)]
     pub id: String,
-    #[pyo3(get, set)]
     pub url: String,
-    #[pyo3(get, set)]
     pub caption: String,
-    #[pyo3(get)]
     pub attrs: HashMap<String, String>,
-    #[pyo3(get)]
-    pub context: Vec<Py<ContentHierarchy>>,
-    #[pyo3(get)]
-    pub rows: Vec<Py<Row>>,
+    pub context: Vec<ContentHierarchy>,
+    pub rows: Vec<Row>,
+    // location of the table element, only available when locations are recorded during extraction
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
 }
 
-#[pymethods]
 impl Table {
-    #[new]
-    #[args(
-        "*",
-        id = "String::new()",
-        url = "String::new()",
-        caption = "String::new()",
-        attrs = "HashMap::new()",
-        context = "Vec::new()",
-        rows = "Vec::new()"
-    )]
-    pub fn new(
-        id: String,
-        url: String,
-        caption: String,
-        attrs: HashMap<String, String>,
-        context: Vec<Py<ContentHierarchy>>,
-        rows: Vec<Py<Row>>,
-    ) -> Self {
-        Self {
-            id,
-            url,
-            caption,
-            attrs,
-            context,
-            rows,
-        }
-    }
-
     /// Span the table by copying values to merged field
-    pub fn span(&self, py: Python) -> PyResult<Table> {
-        if self.rows.len() == 0 {
+    ///
+    /// Return an error containing the position of the cell that prevents the table from being spanned
+    pub fn span(&self) -> Result<Table, RSoupError> {
+        if self.rows.is_empty() {
             return Ok(self.clone());
         }
 
@@ -76,11 +48,9 @@ impl Table {
         // so we may have to iterate several times
 
         let mut cols = vec![0; self.rows.len()];
-        for (i, py_row) in self.rows.iter().enumerate() {
-            let row = py_row.borrow(py);
+        for (i, row) in self.rows.iter().enumerate() {
             cols[i] += row.cells.len();
-            for py_cell in &row.cells {
-                let cell = py_cell.borrow(py);
+            for cell in &row.cells {
                 if cell.rowspan > 1 {
                     for j in 1..cell.rowspan {
                         if i + (j as usize) < cols.len() {
@@ -98,13 +68,12 @@ impl Table {
         // so I decide to not handle that. Hope that we don't have many tables like that.
         // >>> finish find the max #cols
 
-        for py_row in &self.rows {
-            let row = py_row.borrow(py);
+        for row in &self.rows {
             let mut new_row = Vec::with_capacity(row.cells.len());
             let mut pj = 0;
 
             for (cell_index, ocell) in row.cells.iter().enumerate() {
-                let mut cell = ocell.borrow(py).clone();
+                let mut cell = ocell.clone();
                 let original_colspan = cell.colspan;
                 let original_rowspan = cell.rowspan;
                 cell.colspan = 1;
@@ -112,17 +81,30 @@ impl Table {
 
                 // adding cell from the top
                 while pending_ops.contains_key(&(pi, pj)) {
-                    new_row.push(Py::new(py, pending_ops.remove(&(pi, pj)).unwrap())?);
+                    new_row.push(pending_ops.remove(&(pi, pj)).unwrap());
                     pj += 1;
                 }
 
+                cell.origin = Some(CellOrigin {
+                    row: pi as usize,
+                    cell: cell_index,
+                    top: pi as usize,
+                    left: pj as usize,
+                    bottom: (pi as usize + original_rowspan.max(1) as usize).min(self.rows.len()),
+                    right: (pj + original_colspan as i32).min(max_ncols.max(pj + 1)) as usize,
+                });
+
                 // now add cell and expand the column
                 for _ in 0..original_colspan {
                     if pending_ops.contains_key(&(pi, pj)) {
                         // exception, overlapping between colspan and rowspan
-                        return Err(crate::error::OverlapSpanPyError::new_err("".to_owned()).into());
+                        return Err(RSoupError::OverlapSpanError {
+                            row: pi as usize,
+                            cell: cell_index,
+                            col: pj as usize,
+                        });
                     }
-                    new_row.push(Py::new(py, cell.clone())?);
+                    new_row.push(cell.clone());
                     for ioffset in 1..original_rowspan {
                         pending_ops.insert((pi + ioffset as i32, pj), cell.clone());
                     }
@@ -132,10 +114,11 @@ impl Table {
                         // our algorithm cannot handle the case where people are bullying the colspan system, and only can handle the case
                         // where the span that goes beyond the maximum number of columns is in the last column.
                         if cell_index != row.cells.len() - 1 {
-                            return Err(crate::error::InvalidCellSpanPyError::new_err(
-                                "".to_owned(),
-                            )
-                            .into());
+                            return Err(RSoupError::InvalidCellSpanError {
+                                row: pi as usize,
+                                cell: cell_index,
+                                col: pj as usize,
+                            });
                         } else {
                             break;
                         }
@@ -149,7 +132,7 @@ impl Table {
                 //     "\tadding trailing pending ops: {:?}",
                 //     pending_ops.get(&(pi, pj)).unwrap()
                 // );
-                new_row.push(Py::new(py, pending_ops.remove(&(pi, pj)).unwrap())?);
+                new_row.push(pending_ops.remove(&(pi, pj)).unwrap());
                 pj += 1;
             }
 
@@ -158,13 +141,13 @@ impl Table {
             //     pi, new_row, pending_ops
             // );
 
-            data.push(Py::new(
-                py,
-                Row {
-                    cells: new_row,
-                    attrs: row.attrs.clone(),
-                },
-            )?);
+            data.push(Row {
+                cells: new_row,
+                attrs: row.attrs.clone(),
+                section: row.section.clone(),
+                row_group: row.row_group,
+                location: row.location.clone(),
+            });
             pi += 1;
         }
 
@@ -177,144 +160,291 @@ impl Table {
             attrs: self.attrs.clone(),
             context: self.context.clone(),
             rows: data,
+            location: self.location.clone(),
         })
     }
 
-    /// Pad an irregular table (missing cells) to make it become a regular table
+    /// Span the table following the slot assignment of the HTML table processing model
+    /// (https://html.spec.whatwg.org/multipage/tables.html#forming-a-table), hence, it always succeeds.
     ///
-    /// This function only return new table when it's padded, otherwise, None.
-    pub fn pad(&self, py: Python) -> PyResult<Option<Table>> {
-        if self.rows.len() == 0 {
-            return Ok(None);
+    /// Differences to `span`:
+    /// * when cells overlap, the slot is kept by the cell that covers it first and all overlapping cells
+    ///   are flagged with `span_conflict`
+    /// * rowspan is limited to the row group of the cell (see `Row::row_group`), and rowspan 0 spans
+    ///   to the end of the row group
+    /// * columns that no cell starts in (e.g., created by an over-sized colspan) are removed as the browser
+    ///   renders them with zero width
+    pub fn span_html_spec(&self) -> Table {
+        if self.rows.is_empty() {
+            return self.clone();
+        }
+
+        let rows = &self.rows;
+
+        // each slot stores the (row index, cell index) of the cell covering it
+        let mut grid: Vec<Vec<Option<(usize, usize)>>> = vec![Vec::new(); rows.len()];
+        let mut conflicts = HashSet::new();
+        // the area covered by each cell: (top, left, bottom, right)
+        let mut areas = HashMap::new();
+        // exclusive end of the current row group
+        let mut group_end = 0;
+
+        for (ri, row) in rows.iter().enumerate() {
+            if ri >= group_end {
+                group_end = ri + 1;
+                while group_end < rows.len() && rows[group_end].row_group == row.row_group {
+                    group_end += 1;
+                }
+            }
+
+            let mut x = 0;
+            for (ci, cell) in row.cells.iter().enumerate() {
+                while x < grid[ri].len() && grid[ri][x].is_some() {
+                    x += 1;
+                }
+
+                let colspan = (cell.colspan as usize).clamp(1, 1000);
+                let rowspan = if cell.rowspan == 0 {
+                    group_end - ri
+                } else {
+                    (cell.rowspan as usize).min(group_end - ri)
+                };
+
+                areas.insert((ri, ci), (ri, x, ri + rowspan, x + colspan));
+                for grid_row in &mut grid[ri..ri + rowspan] {
+                    if grid_row.len() < x + colspan {
+                        grid_row.resize(x + colspan, None);
+                    }
+                    for slot in &mut grid_row[x..x + colspan] {
+                        match slot {
+                            None => *slot = Some((ri, ci)),
+                            Some(other) => {
+                                conflicts.insert(*other);
+                                conflicts.insert((ri, ci));
+                            }
+                        }
+                    }
+                }
+                x += colspan;
+            }
         }
 
-        let borrowed_rows = self
-            .rows
-            .iter()
-            .map(|py_row| py_row.borrow(py))
+        // keep columns that have at least one cell starting in them
+        let ncols = grid.iter().map(|grid_row| grid_row.len()).max().unwrap();
+        let kept_cols = (0..ncols)
+            .filter(|&x| {
+                grid.iter().any(|grid_row| match grid_row.get(x) {
+                    Some(Some(slot)) => x == 0 || grid_row[x - 1] != Some(*slot),
+                    _ => false,
+                })
+            })
             .collect::<Vec<_>>();
 
-        let ncols = borrowed_rows[0].cells.len();
-        let is_regular_table = borrowed_rows.iter().all(|row| row.cells.len() == ncols);
+        let default_cell = Cell::from_value(RichText::empty());
+
+        let mut data = Vec::with_capacity(rows.len());
+        for (row, grid_row) in rows.iter().zip(grid.iter()) {
+            let slots = kept_cols
+                .iter()
+                .map(|&x| grid_row.get(x).copied().flatten())
+                .collect::<Vec<_>>();
+            // trailing empty slots are left for `pad`
+            let n_slots = slots
+                .iter()
+                .rposition(|slot| slot.is_some())
+                .map_or(0, |i| i + 1);
+
+            let mut new_row = Vec::with_capacity(n_slots);
+            for slot in &slots[..n_slots] {
+                let cell = match slot {
+                    None => default_cell.clone(),
+                    Some((ri, ci)) => {
+                        let mut cell = rows[*ri].cells[*ci].clone();
+                        cell.rowspan = 1;
+                        cell.colspan = 1;
+                        cell.span_conflict = conflicts.contains(&(*ri, *ci));
+
+                        // map the covered area to the columns that are kept
+                        let (top, left, bottom, right) = areas[&(*ri, *ci)];
+                        cell.origin = Some(CellOrigin {
+                            row: *ri,
+                            cell: *ci,
+                            top,
+                            left: kept_cols.partition_point(|&x| x < left),
+                            bottom,
+                            right: kept_cols.partition_point(|&x| x < right),
+                        });
+                        cell
+                    }
+                };
+                new_row.push(cell);
+            }
+
+            data.push(Row {
+                cells: new_row,
+                attrs: row.attrs.clone(),
+                section: row.section.clone(),
+                row_group: row.row_group,
+                location: row.location.clone(),
+            });
+        }
+
+        Table {
+            id: self.id.clone(),
+            url: self.url.clone(),
+            caption: self.caption.clone(),
+            attrs: self.attrs.clone(),
+            context: self.context.clone(),
+            rows: data,
+            location: self.location.clone(),
+        }
+    }
+
+    /// Pad an irregular table (missing cells) to make it become a regular table
+    ///
+    /// This function only return new table when it's padded, otherwise, None.
+    pub fn pad(&self) -> Option<Table> {
+        if self.rows.is_empty() {
+            return None;
+        }
+
+        let ncols = self.rows[0].cells.len();
+        let is_regular_table = self.rows.iter().all(|row| row.cells.len() == ncols);
         if is_regular_table {
-            return Ok(None);
+            return None;
         }
 
-        let max_ncols = borrowed_rows
-            .iter()
-            .map(|row| row.cells.len())
-            .max()
-            .unwrap();
-        let default_cell = Cell {
-            is_header: false,
-            rowspan: 1,
-            colspan: 1,
-            attrs: HashMap::new(),
-            value: Py::new(py, RichText::empty())?,
-        };
+        let max_ncols = self.rows.iter().map(|row| row.cells.len()).max().unwrap();
+        let default_cell = Cell::from_value(RichText::empty());
 
         let mut rows = Vec::with_capacity(self.rows.len());
-        for r in borrowed_rows {
+        for r in &self.rows {
             let mut row = r.clone();
 
             let mut newcell = default_cell.clone();
             // heuristic to match header from the previous cell of the same row
-            newcell.is_header = row
-                .cells
-                .last()
-                .map_or(false, |cell| cell.borrow(py).is_header);
+            newcell.is_header = row.cells.last().is_some_and(|cell| cell.is_header);
 
             while row.cells.len() < max_ncols {
-                row.cells.push(Py::new(py, newcell.clone())?);
+                row.cells.push(newcell.clone());
             }
-            rows.push(Py::new(py, row)?);
+            rows.push(row);
         }
 
-        Ok(Some(Table {
+        Some(Table {
             id: self.id.clone(),
             url: self.url.clone(),
             caption: self.caption.clone(),
             attrs: self.attrs.clone(),
             context: self.context.clone(),
-            rows: rows,
-        }))
+            rows,
+            location: self.location.clone(),
+        })
     }
 
-    pub fn n_rows(&self) -> usize {
-        self.rows.len()
+    /// Infer the number of header rows and header columns of the (spanned) table.
+    /// See `header::detect_header` for the heuristics.
+    pub fn detect_header(&self) -> (usize, usize) {
+        super::header::detect_header(self)
     }
 
-    pub fn shape(&self, py: Python) -> (usize, usize) {
-        if self.rows.len() == 0 {
-            (0, 0)
-        } else {
-            (self.rows.len(), self.rows[0].borrow(py).cells.len())
-        }
+    /// Merge the header rows of the (spanned) table into a path of headers for each column,
+    /// e.g., `["Population", "2010"]`. The number of header rows is detected if not provided.
+    pub fn get_column_header_paths(&self, n_header_rows: Option<usize>) -> Vec<Vec<String>> {
+        super::header::get_column_header_paths(self, n_header_rows)
     }
 
-    pub fn get_cell(&self, py: Python, ri: usize, ci: usize) -> PyResult<Py<Cell>> {
-        if ri >= self.rows.len() {
-            return Err(PyKeyError::new_err(format!(
-                "Key {} is out of rows' range [0, {})",
-                ri,
-                self.rows.len()
-            )));
-        }
-        let row = self.rows[ri].borrow(py);
-        if ci >= row.cells.len() {
-            return Err(PyKeyError::new_err(format!(
-                "Key {} is out of cells' range [0, {})",
-                ci,
-                row.cells.len()
-            )));
-        }
+    /// Get name of each column by flattening its path of headers, e.g., `Population / 2010`.
+    pub fn get_column_names(&self, n_header_rows: Option<usize>, separator: &str) -> Vec<String> {
+        self.get_column_header_paths(n_header_rows)
+            .into_iter()
+            .map(|path| path.join(separator))
+            .collect()
+    }
 
-        Ok(row.cells[ci].clone_ref(py))
+    /// Render the table to a Markdown table. See `render::to_markdown`.
+    pub fn to_markdown(&self, n_header_rows: Option<usize>) -> String {
+        super::render::to_markdown(self, n_header_rows)
     }
 
-    pub fn get_row(&self, py: Python, ri: usize) -> PyResult<Py<Row>> {
-        if ri >= self.rows.len() {
-            return Err(PyKeyError::new_err(format!(
-                "Key {} is out of rows' range [0, {})",
-                ri,
-                self.rows.len()
-            )));
-        }
-        Ok(self.rows[ri].clone_ref(py))
+    /// Render the table to a clean HTML table, merged cells of a spanned table are collapsed back.
+    /// See `render::to_html`.
+    pub fn to_html(&self) -> String {
+        super::render::to_html(self)
     }
 
-    pub fn iter_cells(slf: Py<Table>, py: Python) -> super::cell_iter::CellTIter {
-        super::cell_iter::CellTIter {
-            table: slf.clone_ref(py),
-            row_index: 0,
-            cell_index: 0,
-        }
+    /// Render the table to a LaTeX tabular. See `render::to_latex`.
+    pub fn to_latex(&self, n_header_rows: Option<usize>) -> String {
+        super::render::to_latex(self, n_header_rows)
     }
 
-    pub fn enumerate_cells(slf: Py<Table>, py: Python) -> super::cell_iter::CellTEnumerator {
-        super::cell_iter::CellTEnumerator {
-            table: slf.clone_ref(py),
-            row_index: 0,
-            cell_index: 0,
-        }
+    /// Get features for classifying whether the (spanned) table is a data table or a layout table
+    pub fn get_features(&self) -> TableFeatures {
+        get_table_features(self)
+    }
+
+    /// Score how likely the (spanned) table is a data table, from 0 (layout table) to 1 (data table)
+    pub fn get_data_table_score(&self) -> f64 {
+        get_table_features(self).score()
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
     }
 
-    pub fn iter_rows(slf: Py<Table>, py: Python) -> super::row_iter::RowIter {
-        super::row_iter::RowIter {
-            table: slf.clone_ref(py),
-            row_index: 0,
+    pub fn shape(&self) -> (usize, usize) {
+        if self.rows.is_empty() {
+            (0, 0)
+        } else {
+            (self.rows.len(), self.rows[0].cells.len())
         }
     }
 
+    pub fn get_cell(&self, ri: usize, ci: usize) -> Option<&Cell> {
+        self.rows.get(ri).and_then(|row| row.cells.get(ci))
+    }
+
+    pub fn get_row(&self, ri: usize) -> Option<&Row> {
+        self.rows.get(ri)
+    }
+
+    /// Serialize the table to the binary format: `BINARY_MAGIC`, the version of the format (`BINARY_VERSION`)
+    /// and the table encoded with postcard
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
-        let out = postcard::to_allocvec(self)?;
+        let mut out = Vec::from(BINARY_MAGIC.as_slice());
+        out.push(BINARY_VERSION);
+        out.extend(postcard::to_allocvec(self)?);
         Ok(out)
     }
 
-    #[staticmethod]
-    pub fn from_bytes(bytes: &PyBytes) -> Result<Table> {
-        let table = postcard::from_bytes(bytes.as_bytes())?;
-        Ok(table)
+    /// Deserialize a table from the binary format. Payloads without the magic bytes are written by versions
+    /// before the format had a version marker and are decoded with their old layout.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Table> {
+        let payload = match bytes.strip_prefix(BINARY_MAGIC.as_slice()) {
+            Some(payload) => payload,
+            None => {
+                return match postcard::take_from_bytes::<LegacyTable>(bytes) {
+                    Ok((table, [])) => Ok(table.into()),
+                    _ => Err(RSoupError::UnsupportedFormatError(
+                        "the data is not a table or is written by an older version that cannot be decoded"
+                            .to_owned(),
+                    )
+                    .into()),
+                };
+            }
+        };
+        match payload.split_first() {
+            Some((&BINARY_VERSION, payload)) => Ok(postcard::from_bytes(payload)?),
+            Some((version, _)) => Err(RSoupError::UnsupportedFormatError(format!(
+                "the table is written by a newer version of the binary format ({}), this version reads up to {}",
+                version, BINARY_VERSION
+            ))
+            .into()),
+            None => Err(RSoupError::UnsupportedFormatError(
+                "the version of the binary format is missing".to_owned(),
+            )
+            .into()),
+        }
     }
 
     pub fn to_json(&self) -> Result<String> {
@@ -322,7 +452,6 @@ impl Table {
         Ok(out)
     }
 
-    #[staticmethod]
     pub fn from_json(dat: &str) -> Result<Table> {
         let out = serde_json::from_str(dat)?;
         Ok(out)
@@ -333,84 +462,22 @@ impl Table {
         Ok(out)
     }
 
-    #[staticmethod]
-    pub fn from_base64(b64s: &PyString) -> Result<Table> {
-        let bytes = base64::decode(b64s.to_str()?)?;
-        let table = postcard::from_bytes(&bytes)?;
-        Ok(table)
-    }
-
-    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let o = PyDict::new(py);
-
-        o.set_item("id", &self.id)?;
-        o.set_item("url", &self.url)?;
-        o.set_item("caption", &self.caption)?;
-        o.set_item("attrs", &self.attrs)?;
-        o.set_item(
-            "context",
-            &self
-                .context
-                .iter()
-                .map(|c| c.borrow(py).to_dict(py))
-                .collect::<PyResult<Vec<_>>>()?,
-        )?;
-        o.set_item(
-            "rows",
-            &self
-                .rows
-                .iter()
-                .map(|r| r.borrow(py).to_dict(py))
-                .collect::<PyResult<Vec<_>>>()?,
-        )?;
-
-        Ok(o.into_py(py))
-    }
-
-    pub fn to_list(&self, py: Python) -> PyResult<Vec<Vec<String>>> {
-        Ok(self.rows.iter().map(|r| r.borrow(py).to_list(py)).collect())
+    pub fn from_base64(b64s: &str) -> Result<Table> {
+        let bytes = base64::decode(b64s)?;
+        Table::from_bytes(&bytes)
     }
 
-    fn __getstate__(&self, py: Python) -> PyResult<PyObject> {
-        Ok(PyBytes::new(py, &self.to_bytes()?).into())
+    /// Write the table to CSV (or TSV), the table is spanned if it is not. See `CSVOptions`.
+    pub fn to_csv(&self, options: &CSVOptions) -> Result<String> {
+        super::csv::to_csv(self, options)
     }
 
-    fn __setstate__(&mut self, py: Python, state: PyObject) -> PyResult<()> {
-        let b = state.as_ref(py).downcast::<PyBytes>()?;
-        let slf = Table::from_bytes(b)?;
-
-        self.id = slf.id;
-        self.url = slf.url;
-        self.caption = slf.caption;
-        self.attrs = slf.attrs;
-        self.context = slf.context;
-        self.rows = slf.rows;
-
-        Ok(())
+    /// Read a table from CSV (or TSV). See `CSVOptions`.
+    pub fn from_csv(id: String, url: String, dat: &str, options: &CSVOptions) -> Result<Table> {
+        super::csv::from_csv(id, url, dat, options)
     }
-}
 
-impl fmt::Debug for Table {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Python::with_gil(|py| {
-            f.debug_struct("Table")
-                .field("id", &self.id)
-                .field("url", &self.url)
-                .field("caption", &self.caption)
-                .field("attrs", &self.attrs)
-                .field(
-                    "context",
-                    &self
-                        .context
-                        .iter()
-                        .map(|x| x.borrow(py))
-                        .collect::<Vec<_>>(),
-                )
-                .field(
-                    "rows",
-                    &self.rows.iter().map(|x| x.borrow(py)).collect::<Vec<_>>(),
-                )
-                .finish()
-        })
+    pub fn to_list(&self) -> Vec<Vec<String>> {
+        self.rows.iter().map(|r| r.to_list()).collect()
     }
 }
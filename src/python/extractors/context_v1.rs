@@ -0,0 +1,31 @@
+use pyo3::prelude::*;
+
+use crate::extractors::context_v1::ContextExtractor;
+
+#[pymethods]
+impl ContextExtractor {
+    #[new]
+    #[args(
+        "*",
+        ignored_tags = "None",
+        discard_tags = "None",
+        same_content_level_elements = "None",
+        header_elements = "None",
+        only_keep_inline_tags = "true"
+    )]
+    fn py_new(
+        ignored_tags: Option<Vec<&str>>,
+        discard_tags: Option<Vec<&str>>,
+        same_content_level_elements: Option<Vec<&str>>,
+        header_elements: Option<Vec<&str>>,
+        only_keep_inline_tags: bool,
+    ) -> Self {
+        ContextExtractor::new(
+            ignored_tags,
+            discard_tags,
+            same_content_level_elements,
+            header_elements,
+            only_keep_inline_tags,
+        )
+    }
+}
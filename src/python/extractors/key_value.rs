@@ -0,0 +1,54 @@
+use pyo3::prelude::*;
+
+use crate::extractors::context_v1::ContextExtractor;
+use crate::extractors::key_value::KeyValueExtractor;
+use crate::extractors::Document;
+use crate::misc::url_converter::URLCanonicalization;
+use crate::models::key_value::KeyValueRecord;
+use crate::python::extractors::new_text_options;
+
+#[pymethods]
+impl KeyValueExtractor {
+    #[new]
+    #[args(
+        "*",
+        ignored_tags = "None",
+        discard_tags = "None",
+        keep_tags = "None",
+        only_keep_inline_tags = "true",
+        url_attrs = "None",
+        url_canonicalization = "None"
+    )]
+    fn py_new(
+        context_extractor: ContextExtractor,
+        ignored_tags: Option<Vec<&str>>,
+        discard_tags: Option<Vec<&str>>,
+        keep_tags: Option<Vec<&str>>,
+        only_keep_inline_tags: bool,
+        url_attrs: Option<Vec<(&str, &str)>>,
+        url_canonicalization: Option<URLCanonicalization>,
+    ) -> Self {
+        KeyValueExtractor::new(
+            context_extractor,
+            new_text_options(
+                ignored_tags,
+                discard_tags,
+                keep_tags,
+                only_keep_inline_tags,
+                url_attrs,
+                url_canonicalization,
+            ),
+        )
+    }
+
+    /// Extract key-value records from definition lists and infoboxes of the page
+    #[args(extract_context = "true")]
+    fn extract(
+        &self,
+        url: String,
+        doc: String,
+        extract_context: bool,
+    ) -> PyResult<Vec<KeyValueRecord>> {
+        Ok(self.extract_records(&Document::new(url, doc), extract_context)?)
+    }
+}
@@ -0,0 +1,50 @@
+use pyo3::prelude::*;
+
+use crate::extractors::context_v1::ContextExtractor;
+use crate::extractors::list::ListExtractor;
+use crate::extractors::Document;
+use crate::misc::url_converter::URLCanonicalization;
+use crate::models::list::List;
+use crate::python::extractors::new_text_options;
+
+#[pymethods]
+impl ListExtractor {
+    #[new]
+    #[args(
+        "*",
+        ignored_tags = "None",
+        discard_tags = "None",
+        only_keep_inline_tags = "true",
+        min_items = "1",
+        url_attrs = "None",
+        url_canonicalization = "None"
+    )]
+    fn py_new(
+        context_extractor: ContextExtractor,
+        ignored_tags: Option<Vec<&str>>,
+        discard_tags: Option<Vec<&str>>,
+        only_keep_inline_tags: bool,
+        min_items: usize,
+        url_attrs: Option<Vec<(&str, &str)>>,
+        url_canonicalization: Option<URLCanonicalization>,
+    ) -> Self {
+        ListExtractor::new(
+            context_extractor,
+            new_text_options(
+                ignored_tags,
+                discard_tags,
+                Some(vec![]),
+                only_keep_inline_tags,
+                url_attrs,
+                url_canonicalization,
+            ),
+            min_items,
+        )
+    }
+
+    /// Extract standalone lists of the page
+    #[args(extract_context = "true")]
+    fn extract(&self, url: String, doc: String, extract_context: bool) -> PyResult<Vec<List>> {
+        Ok(self.extract_lists(&Document::new(url, doc), extract_context)?)
+    }
+}
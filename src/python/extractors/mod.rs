@@ -0,0 +1,78 @@
+pub mod context_v1;
+pub mod elementrefview;
+pub mod key_value;
+pub mod list;
+pub mod table;
+
+use pyo3::prelude::*;
+use scraper::Selector;
+
+use self::elementrefview::ElementRefView;
+use crate::extractors::text::TextOptions;
+use crate::extractors::Document;
+use crate::misc::url_converter::URLCanonicalization;
+
+/// Create text options from the arguments of the python extractors, arguments that are not provided
+/// keep their default values
+pub(crate) fn new_text_options(
+    ignored_tags: Option<Vec<&str>>,
+    discard_tags: Option<Vec<&str>>,
+    keep_tags: Option<Vec<&str>>,
+    only_keep_inline_tags: bool,
+    url_attrs: Option<Vec<(&str, &str)>>,
+    url_canonicalization: Option<URLCanonicalization>,
+) -> TextOptions {
+    let mut options = TextOptions {
+        only_keep_inline_tags,
+        url_canonicalization: url_canonicalization.unwrap_or_default(),
+        ..TextOptions::default()
+    };
+    let to_owned = |tags: Vec<&str>| tags.into_iter().map(str::to_owned).collect();
+    if let Some(tags) = ignored_tags {
+        options.ignored_tags = to_owned(tags);
+    }
+    if let Some(tags) = discard_tags {
+        options.discard_tags = to_owned(tags);
+    }
+    if let Some(tags) = keep_tags {
+        options.keep_tags = to_owned(tags);
+    }
+    if let Some(url_attrs) = url_attrs {
+        options.url_attrs = url_attrs
+            .into_iter()
+            .map(|(tag, attr)| (tag.to_owned(), attr.to_owned()))
+            .collect();
+    }
+    options
+}
+
+#[pymethods]
+impl Document {
+    #[new]
+    #[args(record_locations = "false")]
+    fn py_new(url: String, doc: String, record_locations: bool) -> Self {
+        if record_locations {
+            Document::new_with_locations(url, doc)
+        } else {
+            Document::new(url, doc)
+        }
+    }
+
+    #[pyo3(name = "base_url")]
+    fn py_base_url(&self) -> String {
+        self.base_url()
+    }
+
+    pub fn select(&self, query: &str) -> PyResult<Vec<ElementRefView>> {
+        let selector = Selector::parse(query).map_err(|_err| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid css selector: {}", query))
+        })?;
+        // can't return a wrapper of select because select borrows selector, if we convert its scope to static, the selector
+        // will dropped after this function, rendering the select invalid
+        Ok(self
+            .html
+            .select(&selector)
+            .map(|el| ElementRefView::new(el))
+            .collect::<Vec<_>>())
+    }
+}
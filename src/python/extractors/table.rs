@@ -0,0 +1,189 @@
+use pyo3::prelude::*;
+
+use crate::extractors::context_v1::ContextExtractor;
+use crate::extractors::table::{TableExtractor, TableExtractorOptions, TableIdScheme};
+use crate::extractors::Document;
+use crate::misc::url_converter::URLCanonicalization;
+use crate::models::table::{DiscardedTable, Table};
+use crate::python::extractors::new_text_options;
+use crate::python::models::table::PyTable;
+
+#[pymethods]
+impl TableExtractor {
+    #[new]
+    #[args(
+        "*",
+        ignored_tags = "None",
+        discard_tags = "None",
+        keep_tags = "None",
+        only_keep_inline_tags = "true",
+        html_error_forgiveness = "true",
+        extract_nested_tables = "false",
+        html_spec_span = "false",
+        data_table_threshold = "None",
+        url_attrs = "None",
+        url_canonicalization = "None",
+        table_id_scheme = "\"index\"",
+        record_location = "false",
+        include_selector = "None",
+        exclude_selectors = "Vec::new()",
+        extract_aria_tables = "false"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        context_extractor: ContextExtractor,
+        ignored_tags: Option<Vec<&str>>,
+        discard_tags: Option<Vec<&str>>,
+        keep_tags: Option<Vec<&str>>,
+        only_keep_inline_tags: bool,
+        html_error_forgiveness: bool,
+        extract_nested_tables: bool,
+        html_spec_span: bool,
+        data_table_threshold: Option<f64>,
+        url_attrs: Option<Vec<(&str, &str)>>,
+        url_canonicalization: Option<URLCanonicalization>,
+        table_id_scheme: &str,
+        record_location: bool,
+        include_selector: Option<&str>,
+        exclude_selectors: Vec<&str>,
+        extract_aria_tables: bool,
+    ) -> PyResult<Self> {
+        let options = TableExtractorOptions {
+            html_error_forgiveness,
+            extract_nested_tables,
+            html_spec_span,
+            data_table_threshold,
+            table_id_scheme: table_id_scheme.parse::<TableIdScheme>()?,
+            record_location,
+            include_selector: include_selector.map(str::to_owned),
+            exclude_selectors: exclude_selectors.into_iter().map(str::to_owned).collect(),
+            extract_aria_tables,
+            ..TableExtractorOptions::from_text_options(new_text_options(
+                ignored_tags,
+                discard_tags,
+                keep_tags,
+                only_keep_inline_tags,
+                url_attrs,
+                url_canonicalization,
+            ))
+        };
+
+        TableExtractor::new(context_extractor, options)
+            .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))
+    }
+
+    #[args(auto_span = "true", auto_pad = "true", extract_context = "true")]
+    fn extract(
+        &self,
+        py: Python,
+        url: String,
+        doc: String,
+        auto_span: bool,
+        auto_pad: bool,
+        extract_context: bool,
+    ) -> PyResult<Vec<PyTable>> {
+        self.extract_tables(
+            &self.parse_document(url, &doc),
+            auto_span,
+            auto_pad,
+            extract_context,
+        )?
+        .into_iter()
+        .map(|table| PyTable::from_table(py, table))
+        .collect()
+    }
+
+    /// Extract tables from a parsed document. Lines and columns of the locations are only
+    /// available when the document is created with `record_locations`.
+    #[args(auto_span = "true", auto_pad = "true", extract_context = "true")]
+    fn extract_document(
+        &self,
+        py: Python,
+        doc: PyRef<Document>,
+        auto_span: bool,
+        auto_pad: bool,
+        extract_context: bool,
+    ) -> PyResult<Vec<PyTable>> {
+        self.extract_tables(&doc, auto_span, auto_pad, extract_context)?
+            .into_iter()
+            .map(|table| PyTable::from_table(py, table))
+            .collect()
+    }
+
+    /// Extract tables from many pages (pairs of url and html) in parallel without holding the GIL.
+    /// Return the tables of each page, in the same order as the pages. The error of the first page that
+    /// fails is raised, unless `return_exceptions` is true, in which case a pair of (tables, error) is
+    /// returned for each page, the tables of a page that fails are empty and the error of a page that
+    /// succeeds is None.
+    #[args(
+        auto_span = "true",
+        auto_pad = "true",
+        extract_context = "true",
+        num_threads = "None",
+        return_exceptions = "false"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn extract_many(
+        &self,
+        py: Python,
+        pages: Vec<(String, String)>,
+        auto_span: bool,
+        auto_pad: bool,
+        extract_context: bool,
+        num_threads: Option<usize>,
+        return_exceptions: bool,
+    ) -> PyResult<PyObject> {
+        let pages_tables = py.allow_threads(|| {
+            self.extract_tables_many(&pages, auto_span, auto_pad, extract_context, num_threads)
+        })?;
+        let to_py_tables = |tables: Vec<Table>| {
+            tables
+                .into_iter()
+                .map(|table| PyTable::from_table(py, table))
+                .collect::<PyResult<Vec<_>>>()
+        };
+
+        if !return_exceptions {
+            return Ok(pages_tables
+                .into_iter()
+                .map(|tables| to_py_tables(tables?))
+                .collect::<PyResult<Vec<_>>>()?
+                .into_py(py));
+        }
+        Ok(pages_tables
+            .into_iter()
+            .map(|tables| match tables {
+                Ok(tables) => Ok((to_py_tables(tables)?, None)),
+                Err(err) => Ok((Vec::new(), Some(PyErr::from(err).into_py(py)))),
+            })
+            .collect::<PyResult<Vec<(Vec<PyTable>, Option<PyObject>)>>>()?
+            .into_py(py))
+    }
+
+    /// Extract tables from HTML and report tables that are discarded (e.g., empty tables or
+    /// tables that cannot be spanned).
+    #[args(auto_span = "true", auto_pad = "true", extract_context = "true")]
+    fn extract_with_diagnostics(
+        &self,
+        py: Python,
+        url: String,
+        doc: String,
+        auto_span: bool,
+        auto_pad: bool,
+        extract_context: bool,
+    ) -> PyResult<(Vec<PyTable>, Vec<DiscardedTable>)> {
+        let (tables, discarded_tables) = self.extract_tables_with_diagnostics(
+            &self.parse_document(url, &doc),
+            auto_span,
+            auto_pad,
+            extract_context,
+        )?;
+        Ok((
+            tables
+                .into_iter()
+                .map(|table| PyTable::from_table(py, table))
+                .collect::<PyResult<Vec<_>>>()?,
+            discarded_tables,
+        ))
+    }
+}
@@ -0,0 +1,68 @@
+use pyo3::prelude::*;
+
+use crate::misc::url_converter::URLCanonicalization;
+
+#[pymethods]
+impl URLCanonicalization {
+    #[new]
+    #[args(
+        "*",
+        lowercase_host = "false",
+        drop_fragment = "false",
+        remove_tracking_params = "false",
+        normalize_percent_encoding = "false",
+        wikipedia_desktop = "false"
+    )]
+    fn py_new(
+        lowercase_host: bool,
+        drop_fragment: bool,
+        remove_tracking_params: bool,
+        normalize_percent_encoding: bool,
+        wikipedia_desktop: bool,
+    ) -> Self {
+        URLCanonicalization {
+            lowercase_host,
+            drop_fragment,
+            remove_tracking_params,
+            normalize_percent_encoding,
+            wikipedia_desktop,
+        }
+    }
+
+    #[getter]
+    fn lowercase_host(&self) -> bool {
+        self.lowercase_host
+    }
+
+    #[getter]
+    fn drop_fragment(&self) -> bool {
+        self.drop_fragment
+    }
+
+    #[getter]
+    fn remove_tracking_params(&self) -> bool {
+        self.remove_tracking_params
+    }
+
+    #[getter]
+    fn normalize_percent_encoding(&self) -> bool {
+        self.normalize_percent_encoding
+    }
+
+    #[getter]
+    fn wikipedia_desktop(&self) -> bool {
+        self.wikipedia_desktop
+    }
+
+    /// Canonicalize an absolute url, return it unchanged if it is not a valid url
+    #[pyo3(name = "canonicalize")]
+    fn py_canonicalize(&self, url: &str) -> String {
+        match url::Url::parse(url) {
+            Ok(mut url) => {
+                self.canonicalize(&mut url);
+                url.as_str().to_owned()
+            }
+            Err(_) => url.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Python bindings of the library. Models exposed to python keep their children (rows, cells, rich texts)
+//! as python objects so that they can be modified in place, and are converted from/to the native
+//! models in `crate::models` when calling the native algorithms.
+
+pub mod extractors;
+pub mod misc;
+pub mod models;
+pub mod range_iter;
+
+use pyo3::{prelude::*, types::PyList};
+
+use crate::extractors::{
+    context_v1::ContextExtractor, key_value::KeyValueExtractor, list::ListExtractor,
+    table::TableExtractor, Document,
+};
+use crate::misc::url_converter::URLCanonicalization;
+use crate::models::key_value::{KeyValuePair, KeyValueRecord};
+use crate::models::list::{List, ListItem};
+use crate::models::rich_text::{RichText, RichTextElement};
+use crate::models::table::{CellOrigin, DiscardedTable, SourceLocation, TableFeatures};
+use extractors::elementrefview::RichTextConfig;
+use models::content_hierarchy::PyContentHierarchy;
+use models::table::{PyCell, PyRow, PyTable};
+
+#[pymodule]
+fn core(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.setattr("__path__", PyList::empty(py))?;
+
+    m.add_class::<PyTable>()?;
+    m.add_class::<PyRow>()?;
+    m.add_class::<PyCell>()?;
+    m.add_class::<CellOrigin>()?;
+    m.add_class::<DiscardedTable>()?;
+    m.add_class::<TableFeatures>()?;
+    m.add_class::<SourceLocation>()?;
+    m.add_class::<PyContentHierarchy>()?;
+    m.add_class::<RichText>()?;
+    m.add_class::<RichTextConfig>()?;
+    m.add_class::<RichTextElement>()?;
+    m.add_class::<TableExtractor>()?;
+    m.add_class::<ContextExtractor>()?;
+    m.add_class::<KeyValueExtractor>()?;
+    m.add_class::<KeyValueRecord>()?;
+    m.add_class::<KeyValuePair>()?;
+    m.add_class::<ListExtractor>()?;
+    m.add_class::<List>()?;
+    m.add_class::<ListItem>()?;
+    m.add_class::<Document>()?;
+    m.add_class::<URLCanonicalization>()?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use crate::error::into_pyerr;
+use crate::models::{content_hierarchy::ContentHierarchy, rich_text::RichText};
+use crate::python::models::{Owners, Version};
+use postcard::{from_bytes, to_allocvec};
+use pyo3::{prelude::*, types::PyBytes, types::PyDict, types::PyList};
+
+/// Python view of `ContentHierarchy`, rich texts are python objects so that they
+/// can be modified in place
+#[pyclass(module = "rsoup.core", name = "ContentHierarchy")]
+pub struct PyContentHierarchy {
+    #[pyo3(get)]
+    pub level: usize,
+    #[pyo3(get)]
+    pub heading: Py<RichText>,
+    #[pyo3(get)]
+    pub content_before: Vec<Py<RichText>>,
+    #[pyo3(get)]
+    pub content_after: Vec<Py<RichText>>,
+    owners: Owners,
+}
+
+impl PyContentHierarchy {
+    pub fn from_content_hierarchy(py: Python, content: ContentHierarchy) -> PyResult<Self> {
+        Ok(PyContentHierarchy {
+            level: content.level,
+            heading: Py::new(py, content.heading)?,
+            content_before: content
+                .content_before
+                .into_iter()
+                .map(|t| Py::new(py, t))
+                .collect::<PyResult<Vec<_>>>()?,
+            content_after: content
+                .content_after
+                .into_iter()
+                .map(|t| Py::new(py, t))
+                .collect::<PyResult<Vec<_>>>()?,
+            owners: Owners::default(),
+        })
+    }
+
+    /// Register a table containing the level of context, see `Owners`
+    pub(crate) fn add_owner(&mut self, version: &Version) {
+        self.owners.add(version);
+    }
+
+    pub fn to_content_hierarchy(&self, py: Python) -> ContentHierarchy {
+        ContentHierarchy {
+            level: self.level,
+            heading: self.heading.borrow(py).clone(),
+            content_before: self
+                .content_before
+                .iter()
+                .map(|t| t.borrow(py).clone())
+                .collect(),
+            content_after: self
+                .content_after
+                .iter()
+                .map(|t| t.borrow(py).clone())
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyContentHierarchy {
+    #[setter]
+    fn set_level(&mut self, level: usize) {
+        self.level = level;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_heading(&mut self, heading: Py<RichText>) {
+        self.heading = heading;
+        self.owners.mark_modified();
+    }
+
+    #[new]
+    pub fn construct(py: Python) -> PyResult<Self> {
+        PyContentHierarchy::from_content_hierarchy(py, ContentHierarchy::new(0, RichText::empty()))
+    }
+
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let d = PyDict::new(py);
+        d.set_item("level", self.level)?;
+        d.set_item("heading", self.heading.borrow(py).to_dict(py)?)?;
+        d.set_item(
+            "content_before",
+            self.content_before
+                .iter()
+                .map(|t| t.borrow(py).to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        d.set_item(
+            "content_after",
+            self.content_after
+                .iter()
+                .map(|t| t.borrow(py).to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        Ok(d.into_py(py))
+    }
+
+    #[staticmethod]
+    pub fn from_dict(py: Python, obj: &PyDict) -> PyResult<Self> {
+        let level = obj
+            .get_item("level")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("level"))?
+            .extract::<usize>()?;
+
+        let heading = Py::new(
+            py,
+            RichText::from_dict(
+                obj.get_item("heading")
+                    .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("heading"))?
+                    .downcast::<PyDict>()?,
+            )?,
+        )?;
+
+        let content_before = obj
+            .get_item("content_before")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("content_before"))?
+            .downcast::<PyList>()?
+            .iter()
+            .map(|o| Py::new(py, RichText::from_dict(o.downcast::<PyDict>()?)?))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let content_after = obj
+            .get_item("content_after")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("content_after"))?
+            .downcast::<PyList>()?
+            .iter()
+            .map(|o| Py::new(py, RichText::from_dict(o.downcast::<PyDict>()?)?))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        Ok(PyContentHierarchy {
+            level,
+            heading,
+            content_before,
+            content_after,
+            owners: Owners::default(),
+        })
+    }
+
+    pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        let out = to_allocvec(&self.to_content_hierarchy(py)).map_err(into_pyerr)?;
+        Ok(PyBytes::new(py, &out))
+    }
+
+    pub fn __setstate__(&mut self, py: Python, state: &PyBytes) -> PyResult<()> {
+        let content = from_bytes::<ContentHierarchy>(state.as_bytes()).map_err(into_pyerr)?;
+        let owners = std::mem::take(&mut self.owners);
+        *self = PyContentHierarchy::from_content_hierarchy(py, content)?;
+        self.owners = owners;
+        self.owners.mark_modified();
+        Ok(())
+    }
+}
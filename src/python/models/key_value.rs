@@ -0,0 +1,76 @@
+use hashbrown::HashMap;
+use pyo3::prelude::*;
+
+use crate::models::key_value::{KeyValuePair, KeyValueRecord};
+use crate::models::rich_text::RichText;
+use crate::python::models::content_hierarchy::PyContentHierarchy;
+
+#[pymethods]
+impl KeyValueRecord {
+    #[getter]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[getter]
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    #[getter]
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    #[getter]
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    #[getter]
+    fn attrs(&self) -> HashMap<String, String> {
+        self.attrs.clone()
+    }
+
+    #[getter]
+    fn context(&self, py: Python) -> PyResult<Vec<PyContentHierarchy>> {
+        self.context
+            .iter()
+            .map(|c| PyContentHierarchy::from_content_hierarchy(py, c.clone()))
+            .collect()
+    }
+
+    #[getter]
+    fn pairs(&self) -> Vec<KeyValuePair> {
+        self.pairs.clone()
+    }
+
+    #[pyo3(name = "to_list")]
+    fn py_to_list(&self) -> Vec<(String, String)> {
+        self.to_list()
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self) -> PyResult<String> {
+        Ok(self.to_json()?)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_json")]
+    fn py_from_json(dat: &str) -> PyResult<KeyValueRecord> {
+        Ok(KeyValueRecord::from_json(dat)?)
+    }
+}
+
+#[pymethods]
+impl KeyValuePair {
+    #[getter]
+    fn key(&self) -> RichText {
+        self.key.clone()
+    }
+
+    #[getter]
+    fn value(&self) -> RichText {
+        self.value.clone()
+    }
+}
@@ -0,0 +1,87 @@
+use hashbrown::HashMap;
+use pyo3::prelude::*;
+
+use crate::models::list::{List, ListItem};
+use crate::models::rich_text::RichText;
+use crate::python::models::content_hierarchy::PyContentHierarchy;
+
+#[pymethods]
+impl List {
+    #[getter]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[getter]
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    #[getter]
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    #[getter]
+    fn attrs(&self) -> HashMap<String, String> {
+        self.attrs.clone()
+    }
+
+    #[getter]
+    fn context(&self, py: Python) -> PyResult<Vec<PyContentHierarchy>> {
+        self.context
+            .iter()
+            .map(|c| PyContentHierarchy::from_content_hierarchy(py, c.clone()))
+            .collect()
+    }
+
+    #[getter]
+    fn items(&self) -> Vec<ListItem> {
+        self.items.clone()
+    }
+
+    /// Get the items and their nested items in document order
+    #[pyo3(name = "flatten")]
+    fn py_flatten(&self) -> Vec<ListItem> {
+        self.flatten().into_iter().cloned().collect()
+    }
+
+    #[pyo3(name = "to_list")]
+    fn py_to_list(&self) -> Vec<(usize, String)> {
+        self.to_list()
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self) -> PyResult<String> {
+        Ok(self.to_json()?)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_json")]
+    fn py_from_json(dat: &str) -> PyResult<List> {
+        Ok(List::from_json(dat)?)
+    }
+}
+
+#[pymethods]
+impl ListItem {
+    #[getter]
+    fn value(&self) -> RichText {
+        self.value.clone()
+    }
+
+    #[getter]
+    fn list_type(&self) -> &str {
+        &self.list_type
+    }
+
+    #[getter]
+    fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[getter]
+    fn children(&self) -> Vec<ListItem> {
+        self.children.clone()
+    }
+}
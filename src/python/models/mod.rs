@@ -0,0 +1,70 @@
+pub mod content_hierarchy;
+pub mod key_value;
+pub mod list;
+pub mod rich_text;
+pub mod table;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+/// Version of the python view of a table, bumped whenever the table or one of its rows, cells or levels of context
+/// is modified. The native table cached by the view is only valid while the version does not change.
+///
+/// The views are only modified and read while holding the GIL, which orders the modifications and the reads of
+/// the versions across threads, sequentially consistent operations keep the versions correct without relying on it.
+#[derive(Debug, Default)]
+pub(crate) struct Version(Arc<AtomicU64>);
+
+impl Version {
+    pub(crate) fn get(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn bump(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Versions of the tables containing a view. Rows, cells and levels of context are python objects that can be
+/// shared by several tables, so modifying them bumps the version of every table containing them, and only of them.
+#[derive(Debug, Default)]
+pub(crate) struct Owners(Vec<Weak<AtomicU64>>);
+
+impl Owners {
+    /// Register a table containing the view, tables that have been dropped are forgotten
+    pub(crate) fn add(&mut self, version: &Version) {
+        self.0.retain(|owner| owner.strong_count() > 0);
+        if !self
+            .0
+            .iter()
+            .any(|owner| owner.ptr_eq(&Arc::downgrade(&version.0)))
+        {
+            self.0.push(Arc::downgrade(&version.0));
+        }
+    }
+
+    /// Invalidate the native tables cached by the tables containing the view, must be called whenever the view
+    /// is modified
+    pub(crate) fn mark_modified(&self) {
+        for owner in &self.0 {
+            if let Some(version) = owner.upgrade() {
+                version.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+/// Number of in-place modifications of rich texts (e.g., `set_element_attr_by_id`). Rich texts are the native
+/// models exposed to python and do not know the tables containing them, so modifying a rich text in place
+/// invalidates the native tables cached by every table. Replacing the value of a cell only invalidates the
+/// tables containing the cell.
+static N_RICH_TEXT_MODIFICATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Invalidate the native tables cached by the python views, must be called whenever a rich text is modified in place
+pub(crate) fn mark_rich_text_modified() {
+    N_RICH_TEXT_MODIFICATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+pub(crate) fn get_n_rich_text_modifications() -> u64 {
+    N_RICH_TEXT_MODIFICATIONS.load(Ordering::SeqCst)
+}
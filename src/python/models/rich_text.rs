@@ -0,0 +1,375 @@
+use hashbrown::HashMap;
+use postcard::{from_bytes, to_allocvec};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::{prelude::*, types::PyBytes, types::PyDict, types::PyList};
+
+use crate::error::into_pyerr;
+use crate::misc::tree::simple_tree::SimpleTree;
+use crate::models::rich_text::{OffsetUnit, RichText, RichTextElement};
+use crate::python::models::mark_rich_text_modified;
+use crate::python::range_iter::RangeIter;
+
+#[pymethods]
+impl RichText {
+    #[new]
+    pub fn py_new() -> Self {
+        RichText::empty()
+    }
+
+    #[getter]
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(text: &str) -> RichText {
+        RichText::from_str(text)
+    }
+
+    /// Length of the text in bytes (default), characters (`char`) or UTF-16 code units (`utf16`)
+    #[pyo3(name = "len")]
+    #[args(unit = "\"byte\"")]
+    fn py_len(&self, unit: &str) -> PyResult<usize> {
+        Ok(self.len_in(unit.parse::<OffsetUnit>()?))
+    }
+
+    /// Convert an offset into the text between bytes, characters (`char`) and UTF-16 code units (`utf16`),
+    /// return None if the offset is out of the text or not at a character boundary
+    #[pyo3(name = "convert_offset")]
+    fn py_convert_offset(
+        &self,
+        offset: usize,
+        from_unit: &str,
+        to_unit: &str,
+    ) -> PyResult<Option<usize>> {
+        Ok(self.convert_offset(offset, from_unit.parse()?, to_unit.parse()?))
+    }
+
+    /// Get the range (start, end) of an element in bytes (default), characters (`char`) or UTF-16 code units (`utf16`)
+    #[pyo3(name = "get_element_range")]
+    #[args(unit = "\"byte\"")]
+    fn py_get_element_range(&self, id: usize, unit: &str) -> PyResult<(usize, usize)> {
+        if id >= self.element.len() {
+            return Err(PyKeyError::new_err(format!(
+                "Key {} is out of elements' range [0, {})",
+                id,
+                self.element.len()
+            )));
+        }
+        Ok(self.get_element_range(id, unit.parse()?))
+    }
+
+    /// Get the ranges of all elements (ordered by their ids) in bytes (default), characters (`char`) or UTF-16 code units (`utf16`)
+    #[pyo3(name = "get_element_ranges")]
+    #[args(unit = "\"byte\"")]
+    fn py_get_element_ranges(&self, unit: &str) -> PyResult<Vec<(usize, usize)>> {
+        Ok(self.get_element_ranges(unit.parse()?))
+    }
+
+    /// Get a part of the rich text between the offsets in bytes (default), characters (`char`) or UTF-16 code units
+    /// (`utf16`), elements outside of the range are removed
+    #[pyo3(name = "slice")]
+    #[args(unit = "\"byte\"")]
+    fn py_slice(&self, start: usize, end: usize, unit: &str) -> PyResult<RichText> {
+        let unit = unit.parse()?;
+        Ok(self.slice(
+            get_byte_offset(self, start, unit)?,
+            get_byte_offset(self, end, unit)?,
+        )?)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "concat")]
+    #[args(separator = "\"\"")]
+    fn py_concat(texts: Vec<RichText>, separator: &str) -> RichText {
+        RichText::concat(&texts, separator)
+    }
+
+    /// Replace the text between the offsets in bytes (default), characters (`char`) or UTF-16 code units (`utf16`),
+    /// ranges of elements are adjusted accordingly
+    #[pyo3(name = "replace_range")]
+    #[args(unit = "\"byte\"")]
+    fn py_replace_range(
+        &self,
+        start: usize,
+        end: usize,
+        replacement: &str,
+        unit: &str,
+    ) -> PyResult<RichText> {
+        let unit = unit.parse()?;
+        Ok(self.replace_range(
+            get_byte_offset(self, start, unit)?,
+            get_byte_offset(self, end, unit)?,
+            replacement,
+        )?)
+    }
+
+    /// Replace every match of the regex with the replacement (capture groups are referred to as `$1` or `${name}`)
+    #[pyo3(name = "substitute")]
+    fn py_substitute(&self, pattern: &str, replacement: &str) -> PyResult<RichText> {
+        Ok(self.substitute(pattern, replacement)?)
+    }
+
+    #[pyo3(name = "strip")]
+    fn py_strip(&self) -> RichText {
+        self.strip()
+    }
+
+    #[pyo3(name = "split")]
+    fn py_split(&self, separator: &str) -> PyResult<Vec<RichText>> {
+        Ok(self.split(separator)?)
+    }
+
+    pub fn iter_element_id(&self) -> RangeIter {
+        RangeIter {
+            start: 0,
+            end: self.element.len(),
+        }
+    }
+
+    pub fn iter_element_id_preorder(
+        slf: Py<RichText>,
+        py: Python,
+    ) -> RichTextElementIdPreorderIter {
+        RichTextElementIdPreorderIter::new(slf.clone_ref(py))
+    }
+
+    #[pyo3(name = "get_element_tag_by_id")]
+    fn py_get_element_tag_by_id(&self, id: usize) -> String {
+        self.get_element_tag_by_id(id)
+    }
+
+    #[pyo3(name = "get_element_by_id")]
+    fn py_get_element_by_id(&self, id: usize) -> RichTextElement {
+        self.get_element_by_id(id)
+    }
+
+    #[pyo3(name = "set_element_by_id")]
+    fn py_set_element_by_id(&mut self, id: usize, element: RichTextElement) {
+        self.set_element_by_id(id, element);
+        mark_rich_text_modified();
+    }
+
+    #[pyo3(name = "set_element_attr_by_id")]
+    fn py_set_element_attr_by_id(&mut self, id: usize, attr: &str, value: &str) {
+        self.set_element_attr_by_id(id, attr, value);
+        mark_rich_text_modified();
+    }
+
+    #[pyo3(name = "get_element_attr_by_id")]
+    fn py_get_element_attr_by_id(&self, id: usize, attr: &str) -> Option<String> {
+        self.get_element_attr_by_id(id, attr)
+    }
+
+    #[pyo3(name = "to_html")]
+    #[args("*", render_outer_element = "true", render_element_attrs = "false")]
+    fn py_to_html(&self, render_outer_element: bool, render_element_attrs: bool) -> String {
+        self.to_html(render_outer_element, render_element_attrs)
+    }
+
+    #[pyo3(name = "to_markdown")]
+    fn py_to_markdown(&self) -> String {
+        self.to_markdown()
+    }
+
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let tree = PyDict::new(py);
+
+        tree.set_item("root", self.element.get_root_id())?;
+        tree.set_item(
+            "nodes",
+            self.element
+                .iter()
+                .iter()
+                .map(|u| u.to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        tree.set_item("node2children", &self.element.node2children)?;
+
+        let d = PyDict::new(py);
+        d.set_item("text", &self.text)?;
+        d.set_item("element", tree)?;
+        Ok(d.into_py(py))
+    }
+
+    #[staticmethod]
+    pub fn from_dict(obj: &PyDict) -> PyResult<Self> {
+        let text = obj
+            .get_item("text")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("text"))?
+            .extract::<String>()?;
+
+        let elem_obj = obj
+            .get_item("element")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("element"))?
+            .downcast::<PyDict>()?;
+        let root = elem_obj
+            .get_item("root")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("root in element"))?
+            .extract::<usize>()?;
+        let nodes = elem_obj
+            .get_item("nodes")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("nodes in element"))?
+            .downcast::<PyList>()?
+            .iter()
+            .map(|o| RichTextElement::from_dict(o.downcast::<PyDict>()?))
+            .collect::<PyResult<Vec<_>>>()?;
+        let node2children = elem_obj
+            .get_item("node2children")
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyError, _>("node2children in element")
+            })?
+            .extract::<Vec<Vec<usize>>>()?;
+
+        Ok(RichText {
+            text,
+            element: SimpleTree::from_data(root, nodes, node2children),
+        })
+    }
+
+    pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        // Implementing pickling support according to this issue: https://github.com/PyO3/pyo3/issues/100
+        let out = to_allocvec(&self).map_err(into_pyerr)?;
+        Ok(PyBytes::new(py, &out))
+    }
+
+    pub fn __setstate__(&mut self, state: &PyBytes) -> PyResult<()> {
+        *self = from_bytes::<RichText>(state.as_bytes()).map_err(into_pyerr)?;
+        mark_rich_text_modified();
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl RichTextElement {
+    #[getter]
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    #[getter]
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    #[getter]
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    #[getter]
+    fn attrs(&self) -> HashMap<String, String> {
+        self.attrs.clone()
+    }
+
+    fn get_attr(&self, name: &str) -> PyResult<&String> {
+        self.attrs
+            .get(name)
+            .ok_or_else(|| PyKeyError::new_err(format!("{name} not found")))
+    }
+
+    fn has_attr(&self, name: &str) -> PyResult<bool> {
+        Ok(self.attrs.contains_key(name))
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let d = PyDict::new(py);
+        d.set_item("tag", &self.tag)?;
+        d.set_item("start", self.start)?;
+        d.set_item("end", self.end)?;
+        d.set_item("attrs", &self.attrs)?;
+        Ok(d.into_py(py))
+    }
+
+    #[staticmethod]
+    fn from_dict(obj: &PyDict) -> PyResult<RichTextElement> {
+        let tag = obj
+            .get_item("tag")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("tag"))?
+            .extract::<String>()?;
+        let start = obj
+            .get_item("start")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("start"))?
+            .extract::<usize>()?;
+        let end = obj
+            .get_item("end")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("end"))?
+            .extract::<usize>()?;
+        let attrs = obj
+            .get_item("attrs")
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("attrs"))?
+            .extract::<HashMap<String, String>>()?;
+        Ok(RichTextElement {
+            tag,
+            start,
+            end,
+            attrs,
+        })
+    }
+}
+
+#[pyclass(module = "rsoup.core")]
+pub struct RichTextElementIdPreorderIter {
+    text: Py<RichText>,
+    stack: Vec<(usize, usize)>,
+    inited: bool,
+}
+
+impl RichTextElementIdPreorderIter {
+    pub fn new(text: Py<RichText>) -> Self {
+        RichTextElementIdPreorderIter {
+            text,
+            stack: Vec::new(),
+            inited: false,
+        }
+    }
+}
+
+#[pymethods]
+impl RichTextElementIdPreorderIter {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> Option<usize> {
+        let text = self.text.borrow(py);
+        loop {
+            if self.stack.is_empty() {
+                if self.inited {
+                    return None;
+                }
+                self.inited = true;
+                self.stack.push((text.element.get_root_id(), 0));
+                return Some(self.stack[self.stack.len() - 1].0);
+            }
+
+            // current element has been returned previously
+            // so we will try to return its child
+            let n1 = self.stack.len() - 1;
+            let (node, child_index) = self.stack[n1];
+            let node_children = text.element.get_child_ids(node);
+
+            if child_index < node_children.len() {
+                // add this child to stack
+                self.stack.push((node_children[child_index], 0));
+                self.stack[n1].1 += 1;
+                return Some(node_children[child_index]);
+            }
+
+            // no child to return, done at this level, so we move up
+            self.stack.pop();
+        }
+    }
+}
+
+/// Convert an offset in the unit to bytes, raise ValueError if the offset is out of the text or not at a character boundary
+fn get_byte_offset(text: &RichText, offset: usize, unit: OffsetUnit) -> PyResult<usize> {
+    text.convert_offset(offset, unit, OffsetUnit::Byte)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Offset {} is out of the text or not at a character boundary",
+                offset
+            ))
+        })
+}
@@ -0,0 +1,256 @@
+use hashbrown::HashMap;
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::models::rich_text::RichText;
+use crate::models::table::{Cell, CellOrigin, SourceLocation};
+use crate::python::models::{Owners, Version};
+
+/// Python view of `Cell`
+#[pyclass(module = "rsoup.core", name = "Cell")]
+pub struct PyCell {
+    #[pyo3(get)]
+    pub is_header: bool,
+    #[pyo3(get)]
+    pub rowspan: u16,
+    #[pyo3(get)]
+    pub colspan: u16,
+    #[pyo3(get)]
+    pub attrs: HashMap<String, String>,
+    #[pyo3(get)]
+    pub value: Py<RichText>,
+    #[pyo3(get)]
+    pub nested_table_ids: Vec<String>,
+    #[pyo3(get)]
+    pub span_conflict: bool,
+    #[pyo3(get)]
+    pub origin: Option<CellOrigin>,
+    #[pyo3(get)]
+    pub location: Option<SourceLocation>,
+    owners: Owners,
+}
+
+impl PyCell {
+    pub fn from_cell(py: Python, cell: Cell) -> PyResult<Self> {
+        Ok(PyCell {
+            is_header: cell.is_header,
+            rowspan: cell.rowspan,
+            colspan: cell.colspan,
+            attrs: cell.attrs,
+            value: Py::new(py, cell.value)?,
+            nested_table_ids: cell.nested_table_ids,
+            span_conflict: cell.span_conflict,
+            origin: cell.origin,
+            location: cell.location,
+            owners: Owners::default(),
+        })
+    }
+
+    /// Register a table containing the cell, see `Owners`
+    pub(super) fn add_owner(&mut self, version: &Version) {
+        self.owners.add(version);
+    }
+
+    pub fn to_cell(&self, py: Python) -> Cell {
+        Cell {
+            is_header: self.is_header,
+            rowspan: self.rowspan,
+            colspan: self.colspan,
+            attrs: self.attrs.clone(),
+            value: self.value.borrow(py).clone(),
+            nested_table_ids: self.nested_table_ids.clone(),
+            span_conflict: self.span_conflict,
+            origin: self.origin.clone(),
+            location: self.location.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyCell {
+    #[setter]
+    fn set_is_header(&mut self, is_header: bool) {
+        self.is_header = is_header;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_rowspan(&mut self, rowspan: u16) {
+        self.rowspan = rowspan;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_colspan(&mut self, colspan: u16) {
+        self.colspan = colspan;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_value(&mut self, value: Py<RichText>) {
+        self.value = value;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_nested_table_ids(&mut self, nested_table_ids: Vec<String>) {
+        self.nested_table_ids = nested_table_ids;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_span_conflict(&mut self, span_conflict: bool) {
+        self.span_conflict = span_conflict;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_origin(&mut self, origin: Option<CellOrigin>) {
+        self.origin = origin;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_location(&mut self, location: Option<SourceLocation>) {
+        self.location = location;
+        self.owners.mark_modified();
+    }
+
+    #[new]
+    #[args(
+        nested_table_ids = "Vec::new()",
+        span_conflict = "false",
+        origin = "None",
+        location = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        is_header: bool,
+        rowspan: u16,
+        colspan: u16,
+        attrs: HashMap<String, String>,
+        value: Py<RichText>,
+        nested_table_ids: Vec<String>,
+        span_conflict: bool,
+        origin: Option<CellOrigin>,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        PyCell {
+            is_header,
+            rowspan,
+            colspan,
+            attrs,
+            value,
+            nested_table_ids,
+            span_conflict,
+            origin,
+            location,
+            owners: Owners::default(),
+        }
+    }
+
+    /// Whether the cell at the given position of the spanned table is a copy of a merged cell
+    /// and not the top-left cell of the merged area.
+    pub fn is_span_copy(&self, ri: usize, ci: usize) -> bool {
+        self.origin
+            .as_ref()
+            .is_some_and(|origin| origin.top != ri || origin.left != ci)
+    }
+
+    fn __str__(&self, py: Python) -> String {
+        self.value.borrow(py).to_html(true, false)
+    }
+
+    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("is_header", self.is_header)?;
+        o.set_item("rowspan", self.rowspan)?;
+        o.set_item("colspan", self.colspan)?;
+        o.set_item("attrs", &self.attrs)?;
+        o.set_item("value", self.value.borrow(py).to_dict(py)?)?;
+        o.set_item("nested_table_ids", &self.nested_table_ids)?;
+        o.set_item("span_conflict", self.span_conflict)?;
+        o.set_item(
+            "origin",
+            self.origin
+                .as_ref()
+                .map(|origin| origin.to_dict(py))
+                .transpose()?,
+        )?;
+        o.set_item(
+            "location",
+            self.location
+                .as_ref()
+                .map(|location| location.to_dict(py))
+                .transpose()?,
+        )?;
+        Ok(o.into_py(py))
+    }
+}
+
+#[pymethods]
+impl CellOrigin {
+    #[new]
+    fn py_new(
+        row: usize,
+        cell: usize,
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+    ) -> Self {
+        CellOrigin::new(row, cell, top, left, bottom, right)
+    }
+
+    #[getter]
+    fn row(&self) -> usize {
+        self.row
+    }
+
+    #[getter]
+    fn cell(&self) -> usize {
+        self.cell
+    }
+
+    #[getter]
+    fn top(&self) -> usize {
+        self.top
+    }
+
+    #[getter]
+    fn left(&self) -> usize {
+        self.left
+    }
+
+    #[getter]
+    fn bottom(&self) -> usize {
+        self.bottom
+    }
+
+    #[getter]
+    fn right(&self) -> usize {
+        self.right
+    }
+
+    #[pyo3(name = "rowspan")]
+    fn py_rowspan(&self) -> usize {
+        self.rowspan()
+    }
+
+    #[pyo3(name = "colspan")]
+    fn py_colspan(&self) -> usize {
+        self.colspan()
+    }
+
+    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("row", self.row)?;
+        o.set_item("cell", self.cell)?;
+        o.set_item("top", self.top)?;
+        o.set_item("left", self.left)?;
+        o.set_item("bottom", self.bottom)?;
+        o.set_item("right", self.right)?;
+        Ok(o.into_py(py))
+    }
+}
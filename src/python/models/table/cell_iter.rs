@@ -1,10 +1,10 @@
 use pyo3::prelude::*;
 
-use super::{Cell, Row, Table};
+use super::{PyCell, PyRow, PyTable};
 
 #[pyclass(module = "rsoup.core", unsendable)]
 pub struct CellRIter {
-    pub row: Py<Row>,
+    pub row: Py<PyRow>,
     pub cell_index: usize,
 }
 
@@ -14,7 +14,7 @@ impl CellRIter {
         slf
     }
 
-    fn __next__(&mut self, py: Python) -> Option<Py<Cell>> {
+    fn __next__(&mut self, py: Python) -> Option<Py<PyCell>> {
         let row = self.row.borrow(py);
         if self.cell_index >= row.cells.len() {
             None
@@ -28,7 +28,7 @@ impl CellRIter {
 
 #[pyclass(module = "rsoup.core", unsendable)]
 pub struct CellTIter {
-    pub table: Py<Table>,
+    pub table: Py<PyTable>,
     pub row_index: usize,
     pub cell_index: usize,
 }
@@ -39,7 +39,7 @@ impl CellTIter {
         slf
     }
 
-    fn __next__(&mut self, py: Python) -> Option<Py<Cell>> {
+    fn __next__(&mut self, py: Python) -> Option<Py<PyCell>> {
         let table = self.table.borrow(py);
 
         if self.row_index >= table.rows.len() {
@@ -60,7 +60,7 @@ impl CellTIter {
                 return None;
             }
             row = table.rows[self.row_index].borrow(py);
-            if row.cells.len() > 0 {
+            if !row.cells.is_empty() {
                 break;
             }
         }
@@ -72,7 +72,7 @@ impl CellTIter {
 
 #[pyclass(module = "rsoup.core", unsendable)]
 pub struct CellTEnumerator {
-    pub table: Py<Table>,
+    pub table: Py<PyTable>,
     pub row_index: usize,
     pub cell_index: usize,
 }
@@ -83,7 +83,7 @@ impl CellTEnumerator {
         slf
     }
 
-    fn __next__(&mut self, py: Python) -> Option<(usize, usize, Py<Cell>)> {
+    fn __next__(&mut self, py: Python) -> Option<(usize, usize, Py<PyCell>)> {
         let table = self.table.borrow(py);
 
         if self.row_index >= table.rows.len() {
@@ -109,7 +109,7 @@ impl CellTEnumerator {
                 return None;
             }
             row = table.rows[self.row_index].borrow(py);
-            if row.cells.len() > 0 {
+            if !row.cells.is_empty() {
                 break;
             }
         }
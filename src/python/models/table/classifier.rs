@@ -0,0 +1,52 @@
+use pyo3::prelude::*;
+
+use crate::models::table::TableFeatures;
+
+#[pymethods]
+impl TableFeatures {
+    #[getter]
+    fn is_presentation(&self) -> bool {
+        self.is_presentation
+    }
+
+    #[getter]
+    fn has_header_markup(&self) -> bool {
+        self.has_header_markup
+    }
+
+    #[getter]
+    fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    #[getter]
+    fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    #[getter]
+    fn n_non_empty_cells(&self) -> usize {
+        self.n_non_empty_cells
+    }
+
+    #[getter]
+    fn avg_text_len(&self) -> f64 {
+        self.avg_text_len
+    }
+
+    #[getter]
+    fn block_content_ratio(&self) -> f64 {
+        self.block_content_ratio
+    }
+
+    #[getter]
+    fn column_type_uniformity(&self) -> f64 {
+        self.column_type_uniformity
+    }
+
+    /// Score how likely the table is a data table, from 0 (layout table) to 1 (data table)
+    #[pyo3(name = "score")]
+    fn py_score(&self) -> f64 {
+        self.score()
+    }
+}
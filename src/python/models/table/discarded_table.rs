@@ -0,0 +1,36 @@
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::models::table::DiscardedTable;
+
+#[pymethods]
+impl DiscardedTable {
+    #[getter]
+    fn table_index(&self) -> usize {
+        self.table_index
+    }
+
+    #[getter]
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[getter]
+    fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    #[getter]
+    fn span_position(&self) -> Option<(usize, usize, usize)> {
+        self.span_position
+    }
+
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("table_index", self.table_index)?;
+        o.set_item("path", &self.path)?;
+        o.set_item("reason", &self.reason)?;
+        o.set_item("span_position", self.span_position)?;
+        Ok(o.into_py(py))
+    }
+}
@@ -0,0 +1,12 @@
+pub mod cell;
+pub mod cell_iter;
+pub mod classifier;
+pub mod discarded_table;
+pub mod py_table;
+pub mod row;
+pub mod row_iter;
+pub mod source_location;
+
+pub use self::cell::PyCell;
+pub use self::py_table::PyTable;
+pub use self::row::PyRow;
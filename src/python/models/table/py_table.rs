@@ -0,0 +1,465 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use pyo3::{
+    exceptions::PyKeyError,
+    prelude::*,
+    types::{PyBytes, PyDict, PyString},
+};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use super::{PyCell, PyRow};
+use crate::error::into_pyerr;
+use crate::models::table::{CSVOptions, SourceLocation, Table, TableFeatures};
+use crate::python::models::content_hierarchy::PyContentHierarchy;
+use crate::python::models::{get_n_rich_text_modifications, Version};
+
+/// Version of the view and number of modifications of rich texts
+type CacheKey = (u64, u64);
+
+/// Python view of `Table`, rows, cells and context are python objects so that they can be modified in place.
+///
+/// Methods running the native algorithms (e.g., `span`, `detect_header`, `to_markdown`, `to_csv`) need the native
+/// table, which is a deep copy of the python objects. The copy is cached and reused by the next calls until the table,
+/// one of its rows, cells or levels of context (see `Version`), or any rich text (see `N_RICH_TEXT_MODIFICATIONS`)
+/// is modified.
+#[pyclass(module = "rsoup.core", name = "Table")]
+pub struct PyTable {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub url: String,
+    #[pyo3(get)]
+    pub caption: String,
+    #[pyo3(get)]
+    pub attrs: HashMap<String, String>,
+    #[pyo3(get)]
+    pub context: Vec<Py<PyContentHierarchy>>,
+    #[pyo3(get)]
+    pub rows: Vec<Py<PyRow>>,
+    #[pyo3(get)]
+    pub location: Option<SourceLocation>,
+    version: Version,
+    // native table converted from this view and its key when it was converted
+    cache: RefCell<Option<(CacheKey, Arc<Table>)>>,
+}
+
+impl PyTable {
+    pub fn from_table(py: Python, table: Table) -> PyResult<Self> {
+        PyTable {
+            id: table.id,
+            url: table.url,
+            caption: table.caption,
+            attrs: table.attrs,
+            context: table
+                .context
+                .into_iter()
+                .map(|c| Py::new(py, PyContentHierarchy::from_content_hierarchy(py, c)?))
+                .collect::<PyResult<Vec<_>>>()?,
+            rows: table
+                .rows
+                .into_iter()
+                .map(|r| Py::new(py, PyRow::from_row(py, r)?))
+                .collect::<PyResult<Vec<_>>>()?,
+            location: table.location,
+            version: Version::default(),
+            cache: RefCell::new(None),
+        }
+        .with_owned_views(py)
+    }
+
+    /// Register the table as an owner of its rows, cells and levels of context, so that modifying them
+    /// invalidates the cached native table
+    fn with_owned_views(self, py: Python) -> PyResult<Self> {
+        for row in &self.rows {
+            row.try_borrow_mut(py)?.add_owner(py, &self.version)?;
+        }
+        for content in &self.context {
+            content.try_borrow_mut(py)?.add_owner(&self.version);
+        }
+        Ok(self)
+    }
+
+    pub fn to_table(&self, py: Python) -> Table {
+        Table {
+            id: self.id.clone(),
+            url: self.url.clone(),
+            caption: self.caption.clone(),
+            attrs: self.attrs.clone(),
+            context: self
+                .context
+                .iter()
+                .map(|c| c.borrow(py).to_content_hierarchy(py))
+                .collect(),
+            rows: self.rows.iter().map(|r| r.borrow(py).to_row(py)).collect(),
+            location: self.location.clone(),
+        }
+    }
+
+    /// Get the native table, the table is converted (deep copied) from the python objects only if
+    /// it has not been converted since the last modification of the python views
+    pub fn get_table(&self, py: Python) -> Arc<Table> {
+        let key = (self.version.get(), get_n_rich_text_modifications());
+        if let Some((cache_key, table)) = self.cache.borrow().as_ref() {
+            if *cache_key == key {
+                return table.clone();
+            }
+        }
+        let table = Arc::new(self.to_table(py));
+        *self.cache.borrow_mut() = Some((key, table.clone()));
+        table
+    }
+}
+
+#[pymethods]
+impl PyTable {
+    #[setter]
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+        self.version.bump();
+    }
+
+    #[setter]
+    fn set_url(&mut self, url: String) {
+        self.url = url;
+        self.version.bump();
+    }
+
+    #[setter]
+    fn set_caption(&mut self, caption: String) {
+        self.caption = caption;
+        self.version.bump();
+    }
+
+    #[setter]
+    fn set_location(&mut self, location: Option<SourceLocation>) {
+        self.location = location;
+        self.version.bump();
+    }
+
+    #[new]
+    #[args(
+        "*",
+        id = "String::new()",
+        url = "String::new()",
+        caption = "String::new()",
+        attrs = "HashMap::new()",
+        context = "Vec::new()",
+        rows = "Vec::new()",
+        location = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python,
+        id: String,
+        url: String,
+        caption: String,
+        attrs: HashMap<String, String>,
+        context: Vec<Py<PyContentHierarchy>>,
+        rows: Vec<Py<PyRow>>,
+        location: Option<SourceLocation>,
+    ) -> PyResult<Self> {
+        Self {
+            id,
+            url,
+            caption,
+            attrs,
+            context,
+            rows,
+            location,
+            version: Version::default(),
+            cache: RefCell::new(None),
+        }
+        .with_owned_views(py)
+    }
+
+    /// Span the table by copying values to merged field
+    pub fn span(&self, py: Python) -> PyResult<PyTable> {
+        let table = self.get_table(py).span().map_err(into_pyerr)?;
+        PyTable::from_table(py, table)
+    }
+
+    /// Span the table following the slot assignment of the HTML table processing model.
+    /// See `Table::span_html_spec`.
+    pub fn span_html_spec(&self, py: Python) -> PyResult<PyTable> {
+        PyTable::from_table(py, self.get_table(py).span_html_spec())
+    }
+
+    /// Pad an irregular table (missing cells) to make it become a regular table
+    ///
+    /// This function only return new table when it's padded, otherwise, None.
+    pub fn pad(&self, py: Python) -> PyResult<Option<PyTable>> {
+        self.get_table(py)
+            .pad()
+            .map(|table| PyTable::from_table(py, table))
+            .transpose()
+    }
+
+    /// Infer the number of header rows and header columns of the (spanned) table.
+    pub fn detect_header(&self, py: Python) -> (usize, usize) {
+        self.get_table(py).detect_header()
+    }
+
+    /// Merge the header rows of the (spanned) table into a path of headers for each column,
+    /// e.g., `["Population", "2010"]`. The number of header rows is detected if not provided.
+    #[args(n_header_rows = "None")]
+    pub fn get_column_header_paths(
+        &self,
+        py: Python,
+        n_header_rows: Option<usize>,
+    ) -> Vec<Vec<String>> {
+        self.get_table(py).get_column_header_paths(n_header_rows)
+    }
+
+    /// Get name of each column by flattening its path of headers, e.g., `Population / 2010`.
+    #[args(n_header_rows = "None", separator = "\" / \"")]
+    pub fn get_column_names(
+        &self,
+        py: Python,
+        n_header_rows: Option<usize>,
+        separator: &str,
+    ) -> Vec<String> {
+        self.get_table(py)
+            .get_column_names(n_header_rows, separator)
+    }
+
+    /// Render the table to a Markdown table, the table is spanned if it is not.
+    /// The number of header rows is detected if not provided.
+    #[args(n_header_rows = "None")]
+    pub fn to_markdown(&self, py: Python, n_header_rows: Option<usize>) -> String {
+        self.get_table(py).to_markdown(n_header_rows)
+    }
+
+    /// Render the table to a clean HTML table, merged cells of a spanned table are collapsed back
+    /// into cells with rowspan and colspan.
+    pub fn to_html(&self, py: Python) -> String {
+        self.get_table(py).to_html()
+    }
+
+    /// Render the table to a LaTeX tabular, the table is spanned if it is not.
+    /// The number of header rows is detected if not provided.
+    #[args(n_header_rows = "None")]
+    pub fn to_latex(&self, py: Python, n_header_rows: Option<usize>) -> String {
+        self.get_table(py).to_latex(n_header_rows)
+    }
+
+    /// Get features for classifying whether the (spanned) table is a data table or a layout table
+    pub fn get_features(&self, py: Python) -> TableFeatures {
+        self.get_table(py).get_features()
+    }
+
+    /// Score how likely the (spanned) table is a data table, from 0 (layout table) to 1 (data table)
+    pub fn get_data_table_score(&self, py: Python) -> f64 {
+        self.get_table(py).get_data_table_score()
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn shape(&self, py: Python) -> (usize, usize) {
+        if self.rows.is_empty() {
+            (0, 0)
+        } else {
+            (self.rows.len(), self.rows[0].borrow(py).cells.len())
+        }
+    }
+
+    pub fn get_cell(&self, py: Python, ri: usize, ci: usize) -> PyResult<Py<PyCell>> {
+        if ri >= self.rows.len() {
+            return Err(PyKeyError::new_err(format!(
+                "Key {} is out of rows' range [0, {})",
+                ri,
+                self.rows.len()
+            )));
+        }
+        let row = self.rows[ri].borrow(py);
+        if ci >= row.cells.len() {
+            return Err(PyKeyError::new_err(format!(
+                "Key {} is out of cells' range [0, {})",
+                ci,
+                row.cells.len()
+            )));
+        }
+
+        Ok(row.cells[ci].clone_ref(py))
+    }
+
+    pub fn get_row(&self, py: Python, ri: usize) -> PyResult<Py<PyRow>> {
+        if ri >= self.rows.len() {
+            return Err(PyKeyError::new_err(format!(
+                "Key {} is out of rows' range [0, {})",
+                ri,
+                self.rows.len()
+            )));
+        }
+        Ok(self.rows[ri].clone_ref(py))
+    }
+
+    pub fn iter_cells(slf: Py<PyTable>, py: Python) -> super::cell_iter::CellTIter {
+        super::cell_iter::CellTIter {
+            table: slf.clone_ref(py),
+            row_index: 0,
+            cell_index: 0,
+        }
+    }
+
+    pub fn enumerate_cells(slf: Py<PyTable>, py: Python) -> super::cell_iter::CellTEnumerator {
+        super::cell_iter::CellTEnumerator {
+            table: slf.clone_ref(py),
+            row_index: 0,
+            cell_index: 0,
+        }
+    }
+
+    pub fn iter_rows(slf: Py<PyTable>, py: Python) -> super::row_iter::RowIter {
+        super::row_iter::RowIter {
+            table: slf.clone_ref(py),
+            row_index: 0,
+        }
+    }
+
+    pub fn to_bytes(&self, py: Python) -> Result<Vec<u8>> {
+        self.get_table(py).to_bytes()
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(py: Python, bytes: &PyBytes) -> PyResult<PyTable> {
+        PyTable::from_table(py, Table::from_bytes(bytes.as_bytes())?)
+    }
+
+    pub fn to_json(&self, py: Python) -> Result<String> {
+        self.get_table(py).to_json()
+    }
+
+    #[staticmethod]
+    pub fn from_json(py: Python, dat: &str) -> PyResult<PyTable> {
+        PyTable::from_table(py, Table::from_json(dat)?)
+    }
+
+    /// Write the table to CSV (or TSV with `delimiter="\t"`), the table is spanned if it is not.
+    #[args(
+        "*",
+        delimiter = "\",\"",
+        quoting = "\"necessary\"",
+        header = "true",
+        n_header_rows = "None",
+        link_columns = "false"
+    )]
+    pub fn to_csv(
+        &self,
+        py: Python,
+        delimiter: &str,
+        quoting: &str,
+        header: bool,
+        n_header_rows: Option<usize>,
+        link_columns: bool,
+    ) -> PyResult<String> {
+        let options = CSVOptions {
+            delimiter: parse_csv_delimiter(delimiter)?,
+            quoting: quoting.parse()?,
+            header,
+            n_header_rows,
+            link_columns,
+        };
+        Ok(self.get_table(py).to_csv(&options)?)
+    }
+
+    #[staticmethod]
+    #[args(
+        "*",
+        id = "String::new()",
+        url = "String::new()",
+        delimiter = "\",\"",
+        quoting = "\"necessary\"",
+        header = "true",
+        n_header_rows = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_csv(
+        py: Python,
+        dat: &str,
+        id: String,
+        url: String,
+        delimiter: &str,
+        quoting: &str,
+        header: bool,
+        n_header_rows: Option<usize>,
+    ) -> PyResult<PyTable> {
+        let options = CSVOptions {
+            delimiter: parse_csv_delimiter(delimiter)?,
+            quoting: quoting.parse()?,
+            header,
+            n_header_rows,
+            link_columns: false,
+        };
+        PyTable::from_table(py, Table::from_csv(id, url, dat, &options)?)
+    }
+
+    pub fn to_base64(&self, py: Python) -> Result<String> {
+        self.get_table(py).to_base64()
+    }
+
+    #[staticmethod]
+    pub fn from_base64(py: Python, b64s: &PyString) -> PyResult<PyTable> {
+        PyTable::from_table(py, Table::from_base64(b64s.to_str()?)?)
+    }
+
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("id", &self.id)?;
+        o.set_item("url", &self.url)?;
+        o.set_item("caption", &self.caption)?;
+        o.set_item("attrs", &self.attrs)?;
+        o.set_item(
+            "context",
+            &self
+                .context
+                .iter()
+                .map(|c| c.borrow(py).to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        o.set_item(
+            "rows",
+            &self
+                .rows
+                .iter()
+                .map(|r| r.borrow(py).to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        o.set_item(
+            "location",
+            self.location
+                .as_ref()
+                .map(|location| location.to_dict(py))
+                .transpose()?,
+        )?;
+
+        Ok(o.into_py(py))
+    }
+
+    pub fn to_list(&self, py: Python) -> PyResult<Vec<Vec<String>>> {
+        Ok(self.rows.iter().map(|r| r.borrow(py).to_list(py)).collect())
+    }
+
+    fn __getstate__(&self, py: Python) -> PyResult<PyObject> {
+        Ok(PyBytes::new(py, &self.to_bytes(py)?).into())
+    }
+
+    fn __setstate__(&mut self, py: Python, state: PyObject) -> PyResult<()> {
+        let b = state.as_ref(py).downcast::<PyBytes>()?;
+        *self = PyTable::from_bytes(py, b)?;
+        Ok(())
+    }
+}
+
+fn parse_csv_delimiter(delimiter: &str) -> PyResult<u8> {
+    match delimiter.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Delimiter must be a single ASCII character, got {:?}",
+            delimiter
+        ))),
+    }
+}
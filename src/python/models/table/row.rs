@@ -0,0 +1,152 @@
+use hashbrown::HashMap;
+use pyo3::{exceptions::PyKeyError, prelude::*, types::PyDict};
+
+use super::PyCell;
+use crate::models::table::{Row, SourceLocation};
+use crate::python::models::{Owners, Version};
+
+/// Python view of `Row`
+#[pyclass(module = "rsoup.core", name = "Row")]
+pub struct PyRow {
+    #[pyo3(get)]
+    pub cells: Vec<Py<PyCell>>,
+    #[pyo3(get)]
+    pub attrs: HashMap<String, String>,
+    #[pyo3(get)]
+    pub section: String,
+    #[pyo3(get)]
+    pub row_group: usize,
+    #[pyo3(get)]
+    pub location: Option<SourceLocation>,
+    owners: Owners,
+}
+
+impl PyRow {
+    pub fn from_row(py: Python, row: Row) -> PyResult<Self> {
+        Ok(PyRow {
+            cells: row
+                .cells
+                .into_iter()
+                .map(|c| Py::new(py, PyCell::from_cell(py, c)?))
+                .collect::<PyResult<Vec<_>>>()?,
+            attrs: row.attrs,
+            section: row.section,
+            row_group: row.row_group,
+            location: row.location,
+            owners: Owners::default(),
+        })
+    }
+
+    /// Register a table containing the row and its cells, see `Owners`
+    pub(super) fn add_owner(&mut self, py: Python, version: &Version) -> PyResult<()> {
+        self.owners.add(version);
+        for cell in &self.cells {
+            cell.try_borrow_mut(py)?.add_owner(version);
+        }
+        Ok(())
+    }
+
+    pub fn to_row(&self, py: Python) -> Row {
+        Row {
+            cells: self
+                .cells
+                .iter()
+                .map(|c| c.borrow(py).to_cell(py))
+                .collect(),
+            attrs: self.attrs.clone(),
+            section: self.section.clone(),
+            row_group: self.row_group,
+            location: self.location.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyRow {
+    #[setter]
+    fn set_section(&mut self, section: String) {
+        self.section = section;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_row_group(&mut self, row_group: usize) {
+        self.row_group = row_group;
+        self.owners.mark_modified();
+    }
+
+    #[setter]
+    fn set_location(&mut self, location: Option<SourceLocation>) {
+        self.location = location;
+        self.owners.mark_modified();
+    }
+
+    #[new]
+    #[args(section = "\"tbody\".to_owned()", row_group = "0", location = "None")]
+    pub fn new(
+        cells: Vec<Py<PyCell>>,
+        attrs: HashMap<String, String>,
+        section: String,
+        row_group: usize,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        PyRow {
+            cells,
+            attrs,
+            section,
+            row_group,
+            location,
+            owners: Owners::default(),
+        }
+    }
+
+    fn get_cell(&self, py: Python, ci: usize) -> PyResult<Py<PyCell>> {
+        if ci >= self.cells.len() {
+            return Err(PyKeyError::new_err(format!(
+                "Key {} is out of cells' range [0, {})",
+                ci,
+                self.cells.len()
+            )));
+        }
+
+        Ok(self.cells[ci].clone_ref(py))
+    }
+
+    fn iter_cells(slf: Py<PyRow>, py: Python) -> super::cell_iter::CellRIter {
+        super::cell_iter::CellRIter {
+            row: slf.clone_ref(py),
+            cell_index: 0,
+        }
+    }
+
+    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("attrs", &self.attrs)?;
+        o.set_item("section", &self.section)?;
+        o.set_item("row_group", self.row_group)?;
+        o.set_item(
+            "location",
+            self.location
+                .as_ref()
+                .map(|location| location.to_dict(py))
+                .transpose()?,
+        )?;
+        o.set_item(
+            "cells",
+            &self
+                .cells
+                .iter()
+                .map(|c| c.borrow(py).to_dict(py))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
+        Ok(o.into_py(py))
+    }
+
+    pub(super) fn to_list(&self, py: Python) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|c| c.borrow(py).value.borrow(py).text.clone())
+            .collect()
+    }
+}
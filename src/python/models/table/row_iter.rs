@@ -1,10 +1,10 @@
 use pyo3::prelude::*;
 
-use super::{Row, Table};
+use super::{PyRow, PyTable};
 
 #[pyclass(module = "rsoup.core", unsendable)]
 pub struct RowIter {
-    pub table: Py<Table>,
+    pub table: Py<PyTable>,
     pub row_index: usize,
 }
 
@@ -14,7 +14,7 @@ impl RowIter {
         slf
     }
 
-    fn __next__(&mut self, py: Python) -> Option<Py<Row>> {
+    fn __next__(&mut self, py: Python) -> Option<Py<PyRow>> {
         let table = self.table.borrow(py);
         if self.row_index >= table.rows.len() {
             None
@@ -0,0 +1,36 @@
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::models::table::SourceLocation;
+
+#[pymethods]
+impl SourceLocation {
+    #[new]
+    #[args(line = "None", column = "None")]
+    fn py_new(css_path: String, line: Option<usize>, column: Option<usize>) -> Self {
+        SourceLocation::new(css_path, line, column)
+    }
+
+    #[getter]
+    fn css_path(&self) -> &str {
+        &self.css_path
+    }
+
+    #[getter]
+    fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    #[getter]
+    fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    pub(super) fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let o = PyDict::new(py);
+
+        o.set_item("css_path", &self.css_path)?;
+        o.set_item("line", self.line)?;
+        o.set_item("column", self.column)?;
+        Ok(o.into_py(py))
+    }
+}
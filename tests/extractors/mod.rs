@@ -1,3 +1,5 @@
 mod test_context_extractor;
+mod test_key_value_extractor;
+mod test_list_extractor;
 mod test_table_extractor;
 mod test_text_extractor;
@@ -1,6 +1,5 @@
 use crate::get_doc;
 use anyhow::Result;
-use pyo3::Python;
 use rsoup::extractors::context_v1::ContextExtractor;
 use scraper::{Node, Selector};
 
@@ -94,9 +93,7 @@ fn test_context_extractor() -> Result<()> {
 
     let elements = doc.html.select(&selector).collect::<Vec<_>>();
     assert_eq!(elements.len(), 1);
-    let gil = Python::acquire_gil();
-    let py = gil.python();
-    let context = extractor.extract_context(py, *elements[0])?;
+    let context = extractor.extract_context(*elements[0])?;
 
     // println!("{:#?}", context);
     assert_eq!(
@@ -0,0 +1,62 @@
+use crate::get_doc;
+use anyhow::Result;
+use rsoup::extractors::{
+    context_v1::ContextExtractor, key_value::KeyValueExtractor, text::TextOptions,
+};
+
+fn to_list(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_extract_key_values() -> Result<()> {
+    let doc = get_doc("key_values.html")?;
+    let extractor = KeyValueExtractor::new(ContextExtractor::default(), TextOptions::default());
+    let records = extractor.extract_records(&doc, true)?;
+    assert_eq!(records.len(), 3);
+
+    let infobox = &records[0];
+    assert_eq!(infobox.tag, "table");
+    assert_eq!(infobox.title, "Mount Everest");
+    assert!(infobox.id.ends_with("?kv_no=0"));
+    assert_eq!(
+        infobox.to_list()[..2],
+        to_list(&[("Elevation", "8,848.86 m"), ("Location", "Nepal and China"),])
+    );
+    assert_eq!(infobox.pairs[2].key.text, "First ascent");
+    assert!(infobox.pairs[2].value.text.contains("Edmund Hillary"));
+    assert_eq!(
+        infobox.pairs[1].value.get_element_attr_by_id(1, "href"),
+        Some("https://example.org/wiki/Nepal".to_owned())
+    );
+    assert_eq!(
+        infobox.context.last().unwrap().heading.text,
+        "Mount Everest"
+    );
+
+    // the definition list nested in the infobox is not extracted, keys of a group wrapped in a div
+    // do not carry over to the values after the div
+    let dl = &records[1];
+    assert_eq!(dl.tag, "dl");
+    assert_eq!(
+        dl.to_list(),
+        to_list(&[
+            ("Route", "South Col"),
+            ("Path", "South Col"),
+            ("Season", "Spring"),
+            ("Season", "Autumn"),
+        ])
+    );
+    assert_eq!(dl.context.last().unwrap().heading.text, "Climbing");
+
+    // tables of two columns with header cells in the first column
+    assert_eq!(
+        records[2].to_list(),
+        to_list(&[("Height", "8848"), ("Prominence", "8848")])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::get_doc;
+use anyhow::Result;
+use rsoup::extractors::{context_v1::ContextExtractor, list::ListExtractor, text::TextOptions};
+
+#[test]
+fn test_extract_lists() -> Result<()> {
+    let doc = get_doc("lists.html")?;
+    let extractor = |min_items| {
+        ListExtractor::new(
+            ContextExtractor::default(),
+            TextOptions {
+                keep_tags: Vec::new(),
+                ..Default::default()
+            },
+            min_items,
+        )
+    };
+
+    let lists = extractor(1).extract_lists(&doc, true)?;
+    assert_eq!(lists.len(), 2);
+
+    let list = &lists[0];
+    assert_eq!(list.tag, "ul");
+    assert!(list.id.ends_with("?list_no=0"));
+    assert_eq!(
+        list.to_list(),
+        vec![
+            (0, "Asia".to_owned()),
+            (1, "Everest (8848 m)".to_owned()),
+            (1, "K2".to_owned()),
+            (2, "Abruzzi Spur".to_owned()),
+            (0, "Europe".to_owned()),
+            (1, "Mont Blanc".to_owned()),
+        ]
+    );
+    assert_eq!(list.items[0].list_type, "ul");
+    assert_eq!(list.items[0].children[0].list_type, "ol");
+    assert_eq!(
+        list.items[0].children[0]
+            .value
+            .get_element_attr_by_id(1, "href"),
+        Some("https://example.org/wiki/Everest".to_owned())
+    );
+    assert_eq!(
+        list.context.last().unwrap().heading.text,
+        "List of mountains"
+    );
+
+    assert_eq!(lists[1].tag, "ol");
+    assert_eq!(lists[1].to_list(), vec![(0, "Nile".to_owned())]);
+
+    let lists = extractor(2).extract_lists(&doc, false)?;
+    assert_eq!(lists.len(), 1);
+    assert!(lists[0].context.is_empty());
+
+    // nested lists are not part of the text of the items even if the text options keep `ul` and `ol`
+    let options = TextOptions::default();
+    assert!(options.keep_tags.contains(&"ul".to_owned()));
+    let lists =
+        ListExtractor::new(ContextExtractor::default(), options, 1).extract_lists(&doc, false)?;
+    assert_eq!(
+        lists[0].to_list(),
+        extractor(1).extract_lists(&doc, false)?[0].to_list()
+    );
+    assert_eq!(lists[0].items[0].value.text, "Asia");
+
+    Ok(())
+}
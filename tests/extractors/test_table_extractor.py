@@ -96,3 +96,20 @@ def test_table_extractor(extractor: TableExtractor, wikipages: List[Webpage]):
             "Nepal\nChina",
         ],
     ]
+
+
+def test_extract_many(extractor: TableExtractor):
+    pages = [
+        ("http://example.org/a", "<table><tr><td>a</td></tr></table>"),
+        ("not a url", "<table><tr><td>b</td></tr></table>"),
+    ]
+    assert [
+        [t.to_list() for t in tables] for tables in extractor.extract_many(pages[:1])
+    ] == [[[["a"]]]]
+    with pytest.raises(Exception, match="relative URL without a base"):
+        extractor.extract_many(pages)
+
+    results = extractor.extract_many(pages, return_exceptions=True)
+    assert [[t.to_list() for t in tables] for tables, _ in results] == [[[["a"]]], []]
+    assert results[0][1] is None
+    assert isinstance(results[1][1], Exception)
@@ -1,17 +1,41 @@
 use crate::get_doc;
 use anyhow::Result;
-use pyo3::Python;
 use rsoup::{
-    extractors::{context_v1::ContextExtractor, table::TableExtractor, Document},
+    extractors::{
+        context_v1::ContextExtractor,
+        table::{TableExtractor, TableExtractorOptions, TableIdScheme},
+        text::TextOptions,
+        Document,
+    },
     models::table::Table,
 };
 use scraper::Selector;
 
+fn new_extractor(options: TableExtractorOptions) -> TableExtractor {
+    TableExtractor::new(ContextExtractor::default(), options).unwrap()
+}
+
+/// Options of the extractor used by most tests: invalid tables are reported instead of being fixed
+fn strict_options() -> TableExtractorOptions {
+    TableExtractorOptions {
+        html_error_forgiveness: false,
+        ..Default::default()
+    }
+}
+
+fn strict_extractor() -> TableExtractor {
+    new_extractor(strict_options())
+}
+
 fn get_tables(filename: &str, testcase: Option<&str>) -> Result<Vec<Table>> {
-    let gil = Python::acquire_gil();
-    let py = gil.python();
+    get_tables_with_extractor(strict_extractor(), filename, testcase)
+}
 
-    let extractor = TableExtractor::new(ContextExtractor::default(), None, None, None, true, false);
+fn get_tables_with_extractor(
+    extractor: TableExtractor,
+    filename: &str,
+    testcase: Option<&str>,
+) -> Result<Vec<Table>> {
     let mut doc = get_doc(filename)?;
 
     if testcase.is_some() {
@@ -20,29 +44,758 @@ fn get_tables(filename: &str, testcase: Option<&str>) -> Result<Vec<Table>> {
         doc = Document::new(doc.url, el.html());
     }
 
-    Ok(extractor.extract_tables(py, &doc, false, false, false)?)
+    Ok(extractor.extract_tables(&doc, false, false, false)?)
 }
 
 #[test]
 fn test_extract_empty_table() -> Result<()> {
-    let gil = Python::acquire_gil();
-    let py = gil.python();
-
     let tables = get_tables(
         "extractors/table.html",
         Some("infobox-with-nested-opt-empty-tables"),
     )?;
     assert_eq!(tables.len(), 1);
     assert_eq!(
-        tables[0].to_list(py)?,
+        tables[0].to_list(),
         vec![vec!["← 2012", "October 15, 2016", "2020 →",]]
     );
 
     let tables = get_tables("wikipedia/2016_Nova_Scotia_municipal_elections.html", None)?;
     assert_eq!(
-        tables[1].to_list(py)?[0],
+        tables[1].to_list()[0],
         vec!["Mayoral candidate[1]", "Vote", "%",]
     );
 
     Ok(())
 }
+
+#[test]
+fn test_extract_row_groups() -> Result<()> {
+    let tables = get_tables("extractors/table.html", Some("table-with-row-groups"))?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].caption, "Population");
+    assert_eq!(
+        tables[0].to_list(),
+        vec![
+            vec!["City", "Population"],
+            vec!["A", "100"],
+            vec!["B", "200"],
+            vec!["Total", "300"],
+        ]
+    );
+    assert_eq!(
+        tables[0]
+            .rows
+            .iter()
+            .map(|r| r.section.clone())
+            .collect::<Vec<_>>(),
+        vec!["thead", "tbody", "tbody", "tfoot"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_nested_tables() -> Result<()> {
+    let tables = get_tables("extractors/table.html", Some("table-with-nested-tables"))?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(
+        tables[0].to_list(),
+        vec![vec!["Alice", "10"], vec!["Bob", "7"]]
+    );
+
+    // the outer table is reported as discarded
+    let doc = get_doc("extractors/table.html")?;
+    let selector = Selector::parse("#table-with-nested-tables").unwrap();
+    let el = doc.html.select(&selector).next().unwrap();
+    let doc = Document::new(doc.url, el.html());
+    let (_, discarded_tables) =
+        strict_extractor().extract_tables_with_diagnostics(&doc, false, false, false)?;
+    assert_eq!(discarded_tables.len(), 1);
+    assert_eq!(discarded_tables[0].table_index, 0);
+    assert_eq!(discarded_tables[0].reason, "nested");
+    assert_eq!(
+        discarded_tables[0].path,
+        "html > body:nth-child(2) > div:nth-child(1) > table:nth-child(1)"
+    );
+
+    let extractor = new_extractor(TableExtractorOptions {
+        extract_nested_tables: true,
+        ..strict_options()
+    });
+    let tables = get_tables_with_extractor(
+        extractor,
+        "extractors/table.html",
+        Some("table-with-nested-tables"),
+    )?;
+    assert_eq!(tables.len(), 2);
+    assert_eq!(
+        tables[0].to_list(),
+        vec![vec!["Team", "Players"], vec!["A", ""]]
+    );
+    assert_eq!(
+        tables[1].to_list(),
+        vec![vec!["Alice", "10"], vec!["Bob", "7"]]
+    );
+    assert_eq!(
+        tables[0].get_cell(1, 1).unwrap().nested_table_ids,
+        vec![tables[1].id.clone()]
+    );
+    assert!(tables[0]
+        .get_cell(1, 0)
+        .unwrap()
+        .nested_table_ids
+        .is_empty());
+
+    // cells are not linked to nested tables that are discarded
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        r#"<table><tr><th>Team</th><th>Players</th></tr><tr><td>A</td><td>
+            <table>
+                <tr><td>a</td><td rowspan="2">b</td><td>c</td></tr>
+                <tr><td colspan="2">d</td><td>e</td></tr>
+            </table>
+        </td></tr></table>"#
+            .to_owned(),
+    );
+    let (tables, discarded_tables) = new_extractor(TableExtractorOptions {
+        extract_nested_tables: true,
+        ..strict_options()
+    })
+    .extract_tables_with_diagnostics(&doc, true, false, false)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(discarded_tables.len(), 1);
+    assert_eq!(discarded_tables[0].reason, "overlap_span");
+    assert!(tables[0]
+        .get_cell(1, 1)
+        .unwrap()
+        .nested_table_ids
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_with_diagnostics() -> Result<()> {
+    let extractor = strict_extractor();
+    let doc = get_doc("extractors/table.html")?;
+    let selector = Selector::parse("#discarded-tables").unwrap();
+    let el = doc.html.select(&selector).next().unwrap();
+    let doc = Document::new(doc.url, el.html());
+
+    let (tables, discarded_tables) =
+        extractor.extract_tables_with_diagnostics(&doc, true, false, false)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].to_list(), vec![vec!["f"]]);
+
+    assert_eq!(discarded_tables.len(), 2);
+    assert_eq!(discarded_tables[0].table_index, 0);
+    assert_eq!(discarded_tables[0].reason, "empty");
+    assert_eq!(
+        discarded_tables[0].path,
+        "html > body:nth-child(2) > div:nth-child(1) > table:nth-child(1)"
+    );
+    assert_eq!(discarded_tables[0].span_position, None);
+
+    assert_eq!(discarded_tables[1].table_index, 1);
+    assert_eq!(discarded_tables[1].reason, "overlap_span");
+    assert_eq!(
+        discarded_tables[1].path,
+        "html > body:nth-child(2) > div:nth-child(1) > table:nth-child(2)"
+    );
+    assert_eq!(discarded_tables[1].span_position, Some((1, 0, 1)));
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_with_html_spec_span() -> Result<()> {
+    let extractor = new_extractor(TableExtractorOptions {
+        html_spec_span: true,
+        ..strict_options()
+    });
+    let doc = get_doc("extractors/table.html")?;
+    let selector = Selector::parse("#discarded-tables").unwrap();
+    let el = doc.html.select(&selector).next().unwrap();
+    let doc = Document::new(doc.url, el.html());
+
+    let (tables, discarded_tables) =
+        extractor.extract_tables_with_diagnostics(&doc, true, false, false)?;
+    assert_eq!(tables.len(), 2);
+    assert_eq!(discarded_tables.len(), 1);
+    assert_eq!(discarded_tables[0].reason, "empty");
+    assert_eq!(
+        tables[0].to_list(),
+        vec![vec!["a", "b", "c"], vec!["d", "b", "e"]]
+    );
+    assert_eq!(
+        tables[0]
+            .rows
+            .iter()
+            .map(|r| r.cells.iter().map(|c| c.span_conflict).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![vec![false, true, false], vec![true, true, false]]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_html_error_forgiveness() -> Result<()> {
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        r#"<table><tr><td colspan="2;">a</td></tr><tr><td>b</td><td>c</td></tr></table>"#
+            .to_owned(),
+    );
+
+    let tables = new_extractor(TableExtractorOptions::default())
+        .extract_tables(&doc, false, false, false)?;
+    assert_eq!(tables[0].rows[0].cells[0].colspan, 2);
+
+    let result = strict_extractor().extract_tables(&doc, false, false, false);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_detect_header() -> Result<()> {
+    let tables = get_tables("extractors/table.html", Some("header-detection"))?;
+    assert_eq!(
+        tables.iter().map(|t| t.detect_header()).collect::<Vec<_>>(),
+        vec![(1, 1), (1, 0), (0, 0)]
+    );
+
+    let tables = get_tables("table_span.html", None)?;
+    assert_eq!(tables[0].span()?.detect_header(), (3, 0));
+
+    // words parsed as non-finite numbers are not numbers
+    let extractor = new_extractor(TableExtractorOptions::default());
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        "<table><tr><td>Name</td><td>Value</td></tr><tr><td>A</td><td>inf</td></tr><tr><td>B</td><td>NaN</td></tr></table>"
+            .to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, true, true, false)?;
+    assert_eq!(tables[0].detect_header(), (0, 0));
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        "<table><tr><td>Name</td><td>Value</td></tr><tr><td>A</td><td>1e3</td></tr><tr><td>B</td><td>-2.5%</td></tr></table>"
+            .to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, true, true, false)?;
+    assert_eq!(tables[0].detect_header(), (1, 0));
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_layout_tables() -> Result<()> {
+    let tables = get_tables("extractors/table.html", Some("layout-tables"))?;
+    assert_eq!(tables.len(), 3);
+    let scores = tables
+        .iter()
+        .map(|t| t.get_data_table_score())
+        .collect::<Vec<_>>();
+    assert_eq!(scores[0], 0.0);
+    assert!(scores[1] < 0.5);
+    assert!(scores[2] > 0.5);
+
+    let extractor = new_extractor(TableExtractorOptions {
+        data_table_threshold: Some(0.5),
+        ..strict_options()
+    });
+    let tables =
+        get_tables_with_extractor(extractor, "extractors/table.html", Some("layout-tables"))?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].to_list()[0], vec!["Year", "Population"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_many() -> Result<()> {
+    let extractor = strict_extractor();
+    let mut pages = [
+        "table_span.html",
+        "extractors/table.html",
+        "wikipedia/2016_Nova_Scotia_municipal_elections.html",
+    ]
+    .iter()
+    .map(|filename| {
+        let doc = get_doc(filename)?;
+        Ok((doc.url, doc.html.html()))
+    })
+    .collect::<Result<Vec<_>>>()?;
+    // a page that cannot be extracted does not fail the other pages
+    pages.insert(1, ("not a url".to_owned(), pages[0].1.clone()));
+
+    for num_threads in [None, Some(2), Some(2)] {
+        let mut pages_tables =
+            extractor.extract_tables_many(&pages, true, true, true, num_threads)?;
+        assert_eq!(pages_tables.len(), pages.len());
+        assert!(pages_tables.remove(1).is_err());
+        let pages_tables = pages_tables.into_iter().collect::<Result<Vec<_>>>()?;
+        for ((url, html), tables) in pages
+            .iter()
+            .filter(|(url, _)| url != "not a url")
+            .zip(pages_tables.iter())
+        {
+            let doc = Document::new(url.clone(), html.clone());
+            let expected_tables = extractor.extract_tables(&doc, true, true, true)?;
+            assert_eq!(
+                tables
+                    .iter()
+                    .map(|t| (t.id.clone(), t.to_list()))
+                    .collect::<Vec<_>>(),
+                expected_tables
+                    .iter()
+                    .map(|t| (t.id.clone(), t.to_list()))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_with_base_url() -> Result<()> {
+    let doc = Document::new(
+        "https://example.org/archive/2020/page.html".to_owned(),
+        r#"<html><head><base href="https://en.wikipedia.org/wiki/"></head><body>
+            <h1><a href="Heading">Heading</a></h1>
+            <table><tr><td><a href="Mount_Everest">Mount Everest</a></td><td><a href="/wiki/Nepal">Nepal</a></td></tr></table>
+        </body></html>"#
+            .to_owned(),
+    );
+    assert_eq!(doc.base_url(), "https://en.wikipedia.org/wiki/");
+
+    let extractor = strict_extractor();
+    let tables = extractor.extract_tables(&doc, true, true, true)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(
+        tables[0].id,
+        "https://example.org/archive/2020/page.html?table_no=0"
+    );
+    let hrefs = tables[0].rows[0]
+        .cells
+        .iter()
+        .map(|c| c.value.get_element_attr_by_id(1, "href"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hrefs,
+        vec![
+            Some("https://en.wikipedia.org/wiki/Mount_Everest".to_owned()),
+            Some("https://en.wikipedia.org/wiki/Nepal".to_owned()),
+        ]
+    );
+
+    assert_eq!(
+        tables[0].context[1]
+            .heading
+            .get_element_attr_by_id(1, "href"),
+        Some("https://en.wikipedia.org/wiki/Heading".to_owned())
+    );
+
+    let doc = Document::new(
+        "https://example.org/page.html".to_owned(),
+        "<html><body></body></html>".to_owned(),
+    );
+    assert_eq!(doc.base_url(), "https://example.org/page.html");
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_normalize_media_urls() -> Result<()> {
+    let doc = Document::new(
+        "https://example.org/wiki/page.html".to_owned(),
+        r#"<html><body><table><tr>
+            <td><img src="flag.png" srcset="flag-2x.png 2x, flag-3x.png 3x" data-src="/lazy/flag.png"> France</td>
+            <td><a href="mailto:someone@example.org">Contact</a></td>
+        </tr></table></body></html>"#
+            .to_owned(),
+    );
+    let tables = strict_extractor().extract_tables(&doc, true, true, false)?;
+    let img = tables[0].rows[0].cells[0].value.get_element_by_id(1);
+    assert_eq!(img.tag, "img");
+    assert_eq!(img.attrs["src"], "https://example.org/wiki/flag.png");
+    assert_eq!(
+        img.attrs["srcset"],
+        "https://example.org/wiki/flag-2x.png 2x, https://example.org/wiki/flag-3x.png 3x"
+    );
+    assert_eq!(img.attrs["data-src"], "https://example.org/lazy/flag.png");
+    assert_eq!(
+        tables[0].rows[0].cells[1]
+            .value
+            .get_element_attr_by_id(1, "href"),
+        Some("mailto:someone@example.org".to_owned())
+    );
+
+    // only the configured attributes are normalized
+    let tables = new_extractor(TableExtractorOptions {
+        text: TextOptions {
+            url_attrs: vec![("img".to_owned(), "data-src".to_owned())],
+            ..Default::default()
+        },
+        ..strict_options()
+    })
+    .extract_tables(&doc, true, true, false)?;
+    let img = tables[0].rows[0].cells[0].value.get_element_by_id(1);
+    assert_eq!(img.attrs["src"], "flag.png");
+    assert_eq!(img.attrs["data-src"], "https://example.org/lazy/flag.png");
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_table_ids() -> Result<()> {
+    let extractor = new_extractor(TableExtractorOptions {
+        table_id_scheme: TableIdScheme::Fingerprint,
+        ..strict_options()
+    });
+    let tables = r#"
+        <div id="content">
+            <table class="wikitable"><caption>Peaks</caption><tr><th>Name</th><th>Height</th></tr><tr><td>Everest</td><td>8848</td></tr></table>
+            <table class="wikitable"><tr><th>Name</th><th>Height</th></tr><tr><td>K2</td><td>8611</td></tr></table>
+            <table class="wikitable"><tr><th>Name</th><th>Height</th></tr><tr><td>Lhotse</td><td>8516</td></tr></table>
+        </div>"#;
+    let navbox = r#"<table class="navbox"><tr><td>Navigation</td></tr></table>"#;
+
+    let get_ids = |html: String| -> Result<Vec<(String, String)>> {
+        let doc = Document::new("https://example.org/page?lang=en".to_owned(), html);
+        Ok(extractor
+            .extract_tables(&doc, true, true, false)?
+            .into_iter()
+            .map(|t| (t.rows.last().unwrap().cells[0].value.text.clone(), t.id))
+            .collect())
+    };
+
+    let ids = get_ids(format!("<html><body>{}</body></html>", tables))?;
+    let new_ids = get_ids(format!(
+        "<html><body>{}<p>Intro</p>{}</body></html>",
+        navbox, tables
+    ))?;
+    assert_eq!(ids.len(), 3);
+    assert_eq!(new_ids.len(), 4);
+    assert_eq!(&new_ids[1..], &ids[..]);
+    // classes of the ancestors are not part of the fingerprint, but the classes of the table are
+    let new_ids = get_ids(format!(
+        "<html><body class=\"skin-dark page-Peaks\">{}</body></html>",
+        tables
+    ))?;
+    assert_eq!(new_ids, ids);
+    let new_ids = get_ids(format!(
+        "<html><body>{}</body></html>",
+        tables.replace("wikitable", "wikitable sortable")
+    ))?;
+    assert_ne!(new_ids[0].1, ids[0].1);
+
+    assert!(ids[0]
+        .1
+        .starts_with("https://example.org/page?lang=en&table_fp="));
+    // tables with the same structure are distinguished by their order
+    assert_ne!(ids[0].1, ids[1].1);
+    assert_eq!(ids[2].1, format!("{}-1", ids[1].1));
+
+    // the data of tables without header rows is not part of the fingerprint
+    let ids = ["8848", "8849"]
+        .iter()
+        .map(|height| {
+            get_ids(format!(
+                "<table class=\"wikitable\"><tr><td>Everest</td><td>{}</td></tr></table>",
+                height
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(ids[0][0].1, ids[1][0].1);
+
+    Ok(())
+}
+
+#[test]
+fn test_record_locations() -> Result<()> {
+    let url = "https://example.org/page".to_owned();
+    let html = r#"<html>
+<body>
+<p>Intro</p>
+<table id="peaks">
+  <tr><th>Name</th><th>Height</th></tr>
+  <tr
+    class="summit"
+    id="everest">
+    <td rowspan="2">Everest</td>
+    <td>8848</td>
+  </tr>
+  <tr><td>8849</td></tr>
+</table>
+</body>
+</html>"#
+        .to_owned();
+    let doc = Document::new_with_locations(url.clone(), html.clone());
+
+    let extractor = |record_location| {
+        new_extractor(TableExtractorOptions {
+            record_location,
+            ..strict_options()
+        })
+    };
+
+    let tables = extractor(false).extract_tables(&doc, true, true, false)?;
+    assert!(tables[0].location.is_none());
+    assert!(tables[0].rows[0].location.is_none());
+    assert!(tables[0].rows[0].cells[0].location.is_none());
+
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    let table = &tables[0];
+    let location = table.location.as_ref().unwrap();
+    assert_eq!(
+        location.css_path,
+        "html > body:nth-child(2) > table:nth-child(2)"
+    );
+    assert_eq!(location.line, Some(4));
+    assert_eq!(location.column, Some(1));
+    let location = table.rows[0].cells[1].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(5), Some(20)));
+
+    let location = table.rows[1].location.as_ref().unwrap();
+    assert_eq!(
+        location.css_path,
+        "html > body:nth-child(2) > table:nth-child(2) > tbody:nth-child(1) > tr:nth-child(2)"
+    );
+    // the start tag of the row spans multiple lines
+    assert_eq!((location.line, location.column), (Some(6), Some(3)));
+    let location = table.rows[1].cells[1].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(10), Some(5)));
+    // copies of a merged cell have the location of the merged cell
+    assert_eq!(
+        table.rows[2].cells[0].location,
+        table.rows[1].cells[0].location
+    );
+    assert_eq!(
+        table.rows[2].cells[0].location.as_ref().unwrap().line,
+        Some(9)
+    );
+
+    // positions of elements are not recorded when parsing the document without locations
+    let doc = Document::new(url.clone(), html.clone());
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    let location = tables[0].location.as_ref().unwrap();
+    assert_eq!(
+        location.css_path,
+        "html > body:nth-child(2) > table:nth-child(2)"
+    );
+    assert_eq!((location.line, location.column), (None, None));
+
+    // the extractor records positions when it parses the document
+    let doc = extractor(true).parse_document(url, &html);
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    let location = tables[0].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(4), Some(1)));
+    let doc = extractor(false).parse_document("https://example.org/page".to_owned(), &html);
+    assert!(doc.positions.is_none());
+
+    // columns are counted in characters, the byte order mark is not counted
+    let doc = Document::new_with_locations(
+        "https://example.org/page".to_owned(),
+        "\u{feff}é<table>\n  <tr><td>ü</td></tr></table>".to_owned(),
+    );
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    let table = &tables[0];
+    let location = table.location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(1), Some(2)));
+    let location = table.rows[0].cells[0].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(2), Some(7)));
+    assert_eq!(table.rows[0].cells[0].value.text, "ü");
+
+    // `<` in attribute values, comments and scripts does not start a tag
+    let doc = Document::new_with_locations(
+        "https://example.org/page".to_owned(),
+        r#"<table data-x="1<2" title='a<b>c'>
+<!-- <td> --><script>if (a <b) {}</script>
+<tr><td data-y=x<y>1</td><td>2</td></tr></table>"#
+            .to_owned(),
+    );
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    let table = &tables[0];
+    let location = table.location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(1), Some(1)));
+    let location = table.rows[0].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(3), Some(1)));
+    let cells = &table.rows[0].cells;
+    let location = cells[0].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(3), Some(5)));
+    let location = cells[1].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (Some(3), Some(26)));
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_tables_with_selectors() -> Result<()> {
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        r#"<html><body>
+            <table class="wikitable"><tr><td>outside</td></tr></table>
+            <div id="content">
+                <table class="wikitable"><tr><td>data 1</td></tr></table>
+                <table role="presentation"><tr><td>layout</td></tr></table>
+                <div class="navbox"><table class="wikitable"><tr><td>navigation</td></tr></table></div>
+                <table class="wikitable infobox"><tr><td>infobox</td></tr></table>
+                <table><tr><td>data 2</td></tr></table>
+            </div>
+        </body></html>"#
+            .to_owned(),
+    );
+    let extractor = |include_selector: Option<&str>, exclude_selectors: &[&str]| {
+        TableExtractor::new(
+            ContextExtractor::default(),
+            TableExtractorOptions {
+                include_selector: include_selector.map(str::to_owned),
+                exclude_selectors: exclude_selectors.iter().map(|s| (*s).to_owned()).collect(),
+                ..strict_options()
+            },
+        )
+    };
+    let get_texts = |extractor: TableExtractor| -> Result<Vec<String>> {
+        Ok(extractor
+            .extract_tables(&doc, true, true, false)?
+            .into_iter()
+            .map(|t| t.rows[0].cells[0].value.text.clone())
+            .collect())
+    };
+
+    assert_eq!(get_texts(extractor(None, &[])?)?.len(), 6);
+    assert_eq!(
+        get_texts(extractor(
+            Some("#content table"),
+            &[".navbox", ".infobox", "[role=presentation]"]
+        )?)?,
+        vec!["data 1", "data 2"]
+    );
+    assert_eq!(
+        get_texts(extractor(Some("table.wikitable"), &[".navbox"])?)?,
+        vec!["outside", "data 1", "infobox"]
+    );
+    // elements that are not tables are ignored
+    assert_eq!(
+        get_texts(extractor(Some("#content, #content > table"), &[])?)?,
+        vec!["data 1", "layout", "infobox", "data 2"]
+    );
+    assert!(extractor(Some("table["), &[]).is_err());
+
+    // tables are only discarded for containing tables that are targeted by the selectors
+    let doc = Document::new(
+        "https://example.org/page".to_owned(),
+        r#"<table class="wikitable"><tr><td>outer</td><td>
+            <table><tr><td>inner</td></tr></table>
+        </td></tr></table>"#
+            .to_owned(),
+    );
+    for (include_selector, text) in [(Some("table.wikitable"), "outer"), (None, "inner")] {
+        let tables = extractor(include_selector, &[])?.extract_tables(&doc, true, true, false)?;
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].rows[0].cells[0].value.text, text);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_aria_tables() -> Result<()> {
+    let extractor = |extract_aria_tables| {
+        new_extractor(TableExtractorOptions {
+            extract_aria_tables,
+            ..strict_options()
+        })
+    };
+
+    let tables = get_tables_with_extractor(extractor(false), "aria_tables.html", None)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].to_list(), vec![vec!["html table"]]);
+
+    let doc = get_doc("aria_tables.html")?;
+    let tables = extractor(true).extract_tables(&doc, true, true, false)?;
+    assert_eq!(tables.len(), 3);
+
+    let grid = &tables[0];
+    assert_eq!(grid.caption, "Mountains");
+    assert_eq!(
+        grid.to_list(),
+        vec![
+            vec!["Name", "Location", "Location"],
+            vec!["Everest", "Nepal", "Asia"],
+            vec!["Everest", "China", "Asia"],
+        ]
+    );
+    assert!(grid.rows[0].cells.iter().all(|c| c.is_header));
+    assert!(grid.rows[1].cells[0].is_header);
+    assert!(!grid.rows[1].cells[1].is_header);
+    assert_eq!(
+        grid.rows[1].cells[0]
+            .value
+            .get_element_attr_by_id(1, "href"),
+        Some("https://example.org/wiki/Everest".to_owned())
+    );
+    assert_eq!(grid.detect_header(), (1, 1));
+
+    let rivers = &tables[1];
+    assert_eq!(rivers.caption, "Rivers");
+    assert_eq!(
+        rivers.to_list(),
+        vec![
+            vec!["Name", "Length"],
+            vec!["Nile", "6650"],
+            vec!["Amazon", "6400"],
+            vec!["Total", "2"],
+        ]
+    );
+    assert_eq!(
+        rivers
+            .rows
+            .iter()
+            .map(|r| r.section.as_str())
+            .collect::<Vec<_>>(),
+        vec!["thead", "tbody", "tbody", "tfoot"]
+    );
+
+    assert_eq!(tables[2].to_list(), vec![vec!["html table"]]);
+
+    Ok(())
+}
+
+#[test]
+fn test_extract_aria_tables_ignore_html_table_elements() -> Result<()> {
+    let extractor = new_extractor(TableExtractorOptions {
+        extract_aria_tables: true,
+        ..strict_options()
+    });
+
+    let doc = Document::new(
+        "https://example.org".to_owned(),
+        r#"<table><tr><td style="display: table">x</td></tr></table>"#.to_owned(),
+    );
+    let (tables, discarded_tables) =
+        extractor.extract_tables_with_diagnostics(&doc, true, true, false)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].to_list(), vec![vec!["x"]]);
+    assert!(discarded_tables.is_empty());
+
+    // wrappers without rows are not tables, and ARIA tables inside an HTML table do not make it nested
+    let doc = Document::new(
+        "https://example.org".to_owned(),
+        r#"<table><tr>
+            <td><div style="display: table">wrapper</div></td>
+            <td><div role="grid"><div role="row"><div role="cell">inner</div></div></div></td>
+        </tr></table>"#
+            .to_owned(),
+    );
+    let (tables, discarded_tables) =
+        extractor.extract_tables_with_diagnostics(&doc, true, true, false)?;
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[0].to_list(), vec![vec!["wrapper", "inner"]]);
+    assert_eq!(tables[1].to_list(), vec![vec!["inner"]]);
+    assert!(discarded_tables.is_empty());
+
+    Ok(())
+}
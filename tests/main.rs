@@ -5,6 +5,8 @@ use std::{fs, path::Path};
 #[cfg(test)]
 mod extractors;
 #[cfg(test)]
+mod misc;
+#[cfg(test)]
 mod models;
 
 pub fn get_doc(filename: &str) -> Result<Document> {
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use rsoup::misc::url_converter::{URLCanonicalization, URLConverter};
+
+#[test]
+fn test_is_absolute() -> Result<()> {
+    let converter = URLConverter::new("https://example.org/wiki/page?id=1".to_owned())?;
+
+    for url in [
+        "https://example.org",
+        "http://example.org/a",
+        "mailto:someone@example.org",
+        "ftp://example.org/file.txt",
+        "tel:+1-201-555-0123",
+    ] {
+        assert!(converter.is_absolute(url), "{}", url);
+    }
+    for url in [
+        "page.html",
+        "./page.html",
+        "../page.html",
+        "/page.html",
+        "//example.com/page.html",
+        "?q=1",
+        "#frag",
+        "page.html?url=https://example.com",
+    ] {
+        assert!(!converter.is_absolute(url), "{}", url);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_to_absolute() -> Result<()> {
+    let converter = URLConverter::new("https://example.org:8080/wiki/page?id=1#top".to_owned())?;
+
+    for (url, expected_url) in [
+        ("other.html", "https://example.org:8080/wiki/other.html"),
+        ("./other.html", "https://example.org:8080/wiki/other.html"),
+        ("../other.html", "https://example.org:8080/other.html"),
+        ("/other.html", "https://example.org:8080/other.html"),
+        ("//example.com/other.html", "https://example.com/other.html"),
+        ("?q=1", "https://example.org:8080/wiki/page?q=1"),
+        ("#frag", "https://example.org:8080/wiki/page?id=1#frag"),
+        ("", "https://example.org:8080/wiki/page?id=1"),
+    ] {
+        assert_eq!(converter.to_absolute(url)?, expected_url);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_normalize_srcset() -> Result<()> {
+    let converter = URLConverter::new("https://example.org/wiki/page".to_owned())?;
+
+    assert_eq!(
+        converter.normalize_srcset("flag.png 1x, /img/flag-2x.png 2x,https://cdn.org/flag.png 3x"),
+        "https://example.org/wiki/flag.png 1x, https://example.org/img/flag-2x.png 2x, https://cdn.org/flag.png 3x"
+    );
+    assert_eq!(
+        converter.normalize_srcset(" a.png,, b.png 480w , c,d.png (max-width: 600px, 1x) 2x"),
+        "https://example.org/wiki/a.png, https://example.org/wiki/b.png 480w, https://example.org/wiki/c,d.png (max-width: 600px, 1x) 2x"
+    );
+    assert_eq!(converter.normalize_srcset(""), "");
+
+    Ok(())
+}
+
+#[test]
+fn test_canonicalize() -> Result<()> {
+    let converter = URLConverter::new("https://en.m.wikipedia.org/wiki/Page".to_owned())?
+        .with_canonicalization(URLCanonicalization {
+            lowercase_host: true,
+            drop_fragment: true,
+            remove_tracking_params: true,
+            normalize_percent_encoding: true,
+            wikipedia_desktop: true,
+        });
+
+    for (url, expected_url) in [
+        ("Other#History", "https://en.wikipedia.org/wiki/Other"),
+        (
+            "HTTP://Example.ORG:80/a%7eb/%c3%a9?utm_source=x&id=1&fbclid=2&utm_medium=y",
+            "http://example.org/a~b/%C3%A9?id=1",
+        ),
+        (
+            "https://example.org:443/?utm_campaign=z&gclid=3",
+            "https://example.org/",
+        ),
+        (
+            "https://example.org:8443/a%2Fb",
+            "https://example.org:8443/a%2Fb",
+        ),
+        (
+            "https://de.m.wikipedia.org/wiki/Berlin",
+            "https://de.wikipedia.org/wiki/Berlin",
+        ),
+        ("mailto:someone@example.org", "mailto:someone@example.org"),
+    ] {
+        assert_eq!(converter.normalize_url(url), expected_url);
+    }
+
+    // urls are kept as they are by default
+    let converter = URLConverter::new("https://en.m.wikipedia.org/wiki/Page".to_owned())?;
+    assert_eq!(
+        converter.normalize_url("https://en.m.wikipedia.org/wiki/Other?utm_source=x#History"),
+        "https://en.m.wikipedia.org/wiki/Other?utm_source=x#History"
+    );
+
+    Ok(())
+}
@@ -1 +1,2 @@
+pub mod test_rich_text;
 pub mod test_table;
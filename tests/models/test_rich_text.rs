@@ -0,0 +1,245 @@
+use hashbrown::HashSet;
+use rsoup::{
+    extractors::text::get_rich_text,
+    models::rich_text::{OffsetUnit, RichText},
+};
+use scraper::{Html, Selector};
+
+fn get_cell_rich_text(html: &str) -> RichText {
+    let doc = Html::parse_fragment(html);
+    let el = doc.select(&Selector::parse("p").unwrap()).next().unwrap();
+    get_rich_text(&el, &HashSet::new(), true, &HashSet::new(), &HashSet::new())
+}
+
+#[test]
+fn test_element_ranges() {
+    let text =
+        get_cell_rich_text(r#"<p>Thành phố <a href="/hcm">Hồ Chí Minh</a> 🚀 <b>東京</b></p>"#);
+    assert_eq!(text.text, "Thành phố Hồ Chí Minh 🚀 東京");
+
+    assert_eq!(text.get_element_range(1, OffsetUnit::Byte), (13, 27));
+    assert_eq!(text.get_element_range(1, OffsetUnit::Char), (10, 21));
+    assert_eq!(text.get_element_range(1, OffsetUnit::Utf16), (10, 21));
+    // the emoji is one character but two UTF-16 code units
+    assert_eq!(text.get_element_range(2, OffsetUnit::Char), (24, 26));
+    assert_eq!(text.get_element_range(2, OffsetUnit::Utf16), (25, 27));
+
+    let chars = text.text.chars().collect::<Vec<_>>();
+    let (start, end) = text.get_element_range(1, OffsetUnit::Char);
+    assert_eq!(chars[start..end].iter().collect::<String>(), "Hồ Chí Minh");
+
+    for unit in [OffsetUnit::Byte, OffsetUnit::Char, OffsetUnit::Utf16] {
+        assert_eq!(
+            text.get_element_ranges(unit),
+            (0..3)
+                .map(|id| text.get_element_range(id, unit))
+                .collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(text.get_element_ranges(OffsetUnit::Utf16)[0], (0, 27));
+    assert_eq!(text.len_in(OffsetUnit::Byte), 39);
+    assert_eq!(text.len_in(OffsetUnit::Char), 26);
+    assert_eq!(text.len_in(OffsetUnit::Utf16), 27);
+}
+
+#[test]
+fn test_convert_offset() {
+    let text = RichText::from_str("aé🚀b");
+
+    // (byte, char, utf16) offsets of the character boundaries
+    for (byte, char, utf16) in [(0, 0, 0), (1, 1, 1), (3, 2, 2), (7, 3, 4), (8, 4, 5)] {
+        assert_eq!(
+            text.convert_offset(byte, OffsetUnit::Byte, OffsetUnit::Char),
+            Some(char)
+        );
+        assert_eq!(
+            text.convert_offset(byte, OffsetUnit::Byte, OffsetUnit::Utf16),
+            Some(utf16)
+        );
+        assert_eq!(
+            text.convert_offset(char, OffsetUnit::Char, OffsetUnit::Byte),
+            Some(byte)
+        );
+        assert_eq!(
+            text.convert_offset(utf16, OffsetUnit::Utf16, OffsetUnit::Char),
+            Some(char)
+        );
+    }
+
+    // inside a multi-byte character or a surrogate pair, or out of the text
+    assert_eq!(
+        text.convert_offset(2, OffsetUnit::Byte, OffsetUnit::Char),
+        None
+    );
+    assert_eq!(
+        text.convert_offset(3, OffsetUnit::Utf16, OffsetUnit::Byte),
+        None
+    );
+    assert_eq!(
+        text.convert_offset(5, OffsetUnit::Char, OffsetUnit::Byte),
+        None
+    );
+    assert_eq!(
+        text.convert_offset(9, OffsetUnit::Byte, OffsetUnit::Utf16),
+        None
+    );
+}
+
+fn get_links(text: &RichText) -> Vec<(String, &str)> {
+    text.element
+        .iter_node_preorder()
+        .filter(|el| el.tag == "a")
+        .map(|el| (el.attrs["href"].clone(), &text.text[el.start..el.end]))
+        .collect()
+}
+
+#[test]
+fn test_slice_and_split() -> anyhow::Result<()> {
+    let text = get_cell_rich_text(
+        r#"<p>  <a href="/a">Hà Nội</a>, <b>Huế</b> and <a href="/hcm">Hồ Chí Minh</a>  </p>"#,
+    );
+    assert_eq!(text.text, "Hà Nội, Huế and Hồ Chí Minh");
+
+    let part = text.slice(
+        text.text.find("Nội").unwrap(),
+        text.text.find(" and").unwrap(),
+    )?;
+    assert!(part.validate());
+    assert_eq!(part.text, "Nội, Huế");
+    assert_eq!(get_links(&part), vec![("/a".to_owned(), "Nội")]);
+    assert!(text.slice(0, 2).is_err()); // inside `à`
+    assert!(text.slice(5, 4).is_err());
+
+    let parts = text.split(", ")?;
+    assert_eq!(
+        parts.iter().map(|p| p.text.as_str()).collect::<Vec<_>>(),
+        vec!["Hà Nội", "Huế and Hồ Chí Minh"]
+    );
+    assert!(parts.iter().all(|p| p.validate()));
+    assert_eq!(get_links(&parts[0]), vec![("/a".to_owned(), "Hà Nội")]);
+    assert_eq!(
+        get_links(&parts[1]),
+        vec![("/hcm".to_owned(), "Hồ Chí Minh")]
+    );
+
+    let joined = RichText::concat(&parts, " | ");
+    assert!(joined.validate());
+    assert_eq!(joined.text, "Hà Nội | Huế and Hồ Chí Minh");
+    assert_eq!(
+        get_links(&joined),
+        vec![
+            ("/a".to_owned(), "Hà Nội"),
+            ("/hcm".to_owned(), "Hồ Chí Minh")
+        ]
+    );
+
+    // empty elements at a split boundary are kept in the part before the separator
+    let text = get_cell_rich_text(r#"<p>A<img src="/a.png">, B</p>"#);
+    let parts = text.split(", ")?;
+    let tags = |text: &RichText| {
+        text.element
+            .iter_node_preorder()
+            .skip(1)
+            .map(|node| node.tag.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(tags(&parts[0]), vec!["img"]);
+    assert_eq!(parts[0].element.get_node(1).start, 1);
+    assert!(tags(&parts[1]).is_empty());
+
+    // and empty elements at the end of a separator are only kept in the part after it
+    let text = get_cell_rich_text(r#"<p>A;<img src="/b.png">B</p>"#);
+    let parts = text.split(";")?;
+    assert!(tags(&parts[0]).is_empty());
+    assert_eq!(tags(&parts[1]), vec!["img"]);
+    assert_eq!(parts[1].element.get_node(1).start, 0);
+
+    // adjacent slices keep an empty element at their shared edge only once
+    let text = get_cell_rich_text(r#"<p>A<img src="/a.png">B</p>"#);
+    assert_eq!(tags(&text.slice(0, 1)?), vec!["img"]);
+    assert!(tags(&text.slice(1, 2)?).is_empty());
+    assert_eq!(tags(&text.slice(0, 2)?), vec!["img"]);
+    let text = get_cell_rich_text(r#"<p><img src="/a.png">AB</p>"#);
+    assert_eq!(tags(&text.slice(0, 1)?), vec!["img"]);
+    Ok(())
+}
+
+#[test]
+fn test_replace() -> anyhow::Result<()> {
+    let text = get_cell_rich_text(
+        r#"<p><a href="/hcm">Hồ Chí Minh</a> [1] city, <a href="/vn">Việt Nam</a>[2]</p>"#,
+    );
+
+    let cleaned = text.substitute(r"\s*\[\d+\]", "")?;
+    assert!(cleaned.validate());
+    assert_eq!(cleaned.text, "Hồ Chí Minh city, Việt Nam");
+    assert_eq!(
+        get_links(&cleaned),
+        vec![
+            ("/hcm".to_owned(), "Hồ Chí Minh"),
+            ("/vn".to_owned(), "Việt Nam")
+        ]
+    );
+
+    // the link containing the range is resized, the link partially overlapping it is clipped
+    let start = text.text.find("Chí").unwrap();
+    let end = text.text.find(" [1]").unwrap();
+    let replaced = text.replace_range(start, end, "C. M.")?;
+    assert!(replaced.validate());
+    assert_eq!(
+        get_links(&replaced),
+        vec![
+            ("/hcm".to_owned(), "Hồ C. M."),
+            ("/vn".to_owned(), "Việt Nam")
+        ]
+    );
+    let replaced = text.replace_range(start, text.text.find("Nam").unwrap(), "")?;
+    assert!(replaced.validate());
+    assert_eq!(replaced.text, "Hồ Nam[2]");
+    assert_eq!(
+        get_links(&replaced),
+        vec![("/hcm".to_owned(), "Hồ "), ("/vn".to_owned(), "Nam")]
+    );
+
+    let swapped =
+        RichText::from_str("  2020-01-31 ").substitute(r"(\d+)-(\d+)-(\d+)", "$3/$2/$1")?;
+    assert_eq!(swapped.strip().text, "31/01/2020");
+    assert!(swapped.strip().validate());
+    assert!(text.substitute("(", "").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_to_markdown() {
+    let text = get_cell_rich_text(
+        r#"<p><a href="/wiki/C_(language)">C</a> is <b>fast </b>and <i>low-level</i>: <code>a*b</code>, x<sup>2</sup> [1] #1</p>"#,
+    );
+    assert_eq!(
+        text.to_markdown(),
+        r"[C](/wiki/C_%28language%29) is **fast** and *low-level*: `a*b`, x<sup>2</sup> \[1\] #1"
+    );
+    // code containing backticks is fenced by a longer run of backticks
+    let text = get_cell_rich_text(r#"<p><code>a``b</code> and <code>`c</code></p>"#);
+    assert_eq!(text.to_markdown(), "```a``b``` and `` `c ``");
+
+    let doc = Html::parse_fragment(
+        r#"<div><ul><li>- first</li><li>second<ol start="3"><li>third</li><li><em>4. fourth</em></li></ol></li></ul></div>"#,
+    );
+    let el = doc.select(&Selector::parse("div").unwrap()).next().unwrap();
+    let keep_tags = HashSet::from_iter(["ul", "ol", "li"].into_iter().map(str::to_owned));
+    let text = get_rich_text(&el, &HashSet::new(), true, &HashSet::new(), &keep_tags);
+    assert_eq!(
+        text.to_markdown(),
+        "- \\- first\n- second\n  3. third\n  4. *4\\. fourth*"
+    );
+}
+
+#[test]
+fn test_to_html() {
+    let text = get_cell_rich_text(r#"<p>a &lt; b <a title="go">c &gt; d</a></p>"#);
+    assert_eq!(text.to_html(false, false), "a < b <a>c > d</a>");
+    assert_eq!(
+        text.to_html(true, true),
+        r#"<p>a < b <a title="go">c > d</a></p>"#
+    );
+}
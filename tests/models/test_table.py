@@ -0,0 +1,21 @@
+from __future__ import annotations
+
+from rsoup.core import Cell, RichText, Row, Table
+
+
+def test_modify_shared_cells():
+    cell = Cell(False, 1, 1, {}, RichText.from_str("a"))
+    row = Row([cell, Cell(False, 1, 1, {}, RichText.from_str("b"))], {})
+    t1 = Table(id="t1", rows=[row])
+    t2 = Table(id="t2", rows=[row])
+    assert t1.span().to_list() == [["a", "b"]]
+    assert t2.span().to_list() == [["a", "b"]]
+
+    # modifying a cell invalidates the cached copies of every table containing it
+    cell.value = RichText.from_str("c")
+    assert t1.span().to_list() == [["c", "b"]]
+    assert t2.span().to_list() == [["c", "b"]]
+
+    # so does modifying a rich text in place
+    cell.value.set_element_attr_by_id(0, "class", "x")
+    assert t1.span().get_cell(0, 0).value.get_element_attr_by_id(0, "class") == "x"
@@ -1,33 +1,47 @@
 use crate::get_doc;
 use anyhow::Result;
-use pyo3::Python;
 use rsoup::{
-    extractors::{context_v1::ContextExtractor, table::TableExtractor},
-    models::table::Table,
+    extractors::{
+        context_v1::ContextExtractor,
+        table::{TableExtractor, TableExtractorOptions},
+        Document,
+    },
+    models::table::{
+        table::{BINARY_MAGIC, BINARY_VERSION},
+        CSVOptions, CSVQuoting, CellOrigin, Table,
+    },
 };
+use scraper::Selector;
+use std::{fs, path::Path};
 
-fn get_tables(filename: &str) -> Result<Vec<Table>> {
-    let gil = Python::acquire_gil();
-    let py = gil.python();
+/// Extractor that reports invalid tables instead of fixing them
+fn strict_extractor() -> TableExtractor {
+    TableExtractor::new(
+        ContextExtractor::default(),
+        TableExtractorOptions {
+            html_error_forgiveness: false,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
 
-    let extractor = TableExtractor::new(ContextExtractor::default(), None, None, None, true, false);
+fn get_tables(filename: &str) -> Result<Vec<Table>> {
+    let extractor = strict_extractor();
     let doc = get_doc(filename)?;
 
-    Ok(extractor.extract_tables(py, &doc, false, false, false)?)
+    Ok(extractor.extract_tables(&doc, false, false, false)?)
 }
 
 #[test]
 fn test_span() -> Result<()> {
-    let gil = Python::acquire_gil();
-    let py = gil.python();
-
     let tables = get_tables("table_span.html")?;
     let t0 = &tables[0];
 
-    let t0prime = t0.span(py)?;
+    let t0prime = t0.span()?;
 
     assert_eq!(
-        t0prime.to_list(py)?,
+        t0prime.to_list(),
         vec![
             vec![
                 "Mountain name(s)",
@@ -72,5 +86,406 @@ fn test_span() -> Result<()> {
         ]
     );
 
+    let height = t0prime.get_cell(1, 2).unwrap();
+    assert_eq!(height.origin, Some(CellOrigin::new(0, 1, 0, 1, 2, 3)));
+    assert!(height.is_span_copy(1, 2));
+    assert!(!t0prime.get_cell(0, 1).unwrap().is_span_copy(0, 1));
+    assert!(!t0.get_cell(0, 1).unwrap().is_span_copy(0, 1));
+
+    Ok(())
+}
+
+#[test]
+fn test_span_html_spec() -> Result<()> {
+    let tables = get_tables("table_span.html")?;
+    let t0 = &tables[0];
+
+    assert_eq!(t0.span_html_spec().to_list(), t0.span()?.to_list());
+    assert!(t0
+        .span_html_spec()
+        .rows
+        .iter()
+        .all(|r| r.cells.iter().all(|c| !c.span_conflict)));
+
+    // rowspan does not go past the row group containing the cell
+    let extractor = strict_extractor();
+    let doc = Document::new(
+        "https://example.org/".to_owned(),
+        "<table><tbody><tr><td rowspan=3>a</td><td>b</td></tr></tbody><tbody><tr><td>c</td><td>d</td></tr></tbody></table>".to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, false, false, false)?;
+    assert_eq!(
+        tables[0].span_html_spec().to_list(),
+        vec![vec!["a", "b"], vec!["c", "d"]]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_column_header_paths() -> Result<()> {
+    let tables = get_tables("table_span.html")?;
+    let t0 = tables[0].span()?;
+
+    assert_eq!(
+        t0.get_column_header_paths(None),
+        vec![
+            vec!["Mountain name(s)"],
+            vec!["Height (rounded)", "m"],
+            vec!["Height (rounded)", "ft"],
+            vec!["Range"],
+            vec!["Ascents before 2004", "1st"],
+            vec!["Ascents before 2004", "successful", "y"],
+            vec!["Ascents before 2004", "successful", "n"],
+            vec!["Country"],
+        ]
+    );
+    assert_eq!(
+        t0.get_column_names(Some(2), " / "),
+        vec![
+            "Mountain name(s)",
+            "Height (rounded)",
+            "Height (rounded)",
+            "Range",
+            "Ascents before 2004 / 1st",
+            "Ascents before 2004 / successful",
+            "Ascents before 2004 / successful",
+            "Country",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_render_table() -> Result<()> {
+    let tables = get_tables("table_render.html")?;
+    let table = &tables[0];
+    let spanned_table = table.span()?;
+
+    assert_eq!(
+        table.to_markdown(None),
+        [
+            "| City | Population / 2010 | Population / 2020 |",
+            "| --- | --- | --- |",
+            "| [Huế](https://example.org/wiki/Hu%E1%BA%BF) | **340,000** | 652\\_572 |",
+            "| Đà Lạt \\| Lâm Đồng | n/a | n/a |",
+        ]
+        .join("\n")
+    );
+    assert_eq!(spanned_table.to_markdown(None), table.to_markdown(None));
+    // header cells are only merged when they are copies of the same cell, not when they have the same text
+    for (html, header) in [
+        (
+            "<tr><th>Score</th></tr><tr><th>Score</th></tr>",
+            "| Score / Score |",
+        ),
+        ("<tr><th rowspan=2>Score</th></tr><tr></tr>", "| Score |"),
+    ] {
+        let doc = Document::new(
+            "https://example.org/".to_owned(),
+            format!("<table>{}<tr><td>1</td></tr></table>", html),
+        );
+        let tables = strict_extractor().extract_tables(&doc, false, false, false)?;
+        assert_eq!(
+            tables[0].to_markdown(Some(2)),
+            [header, "| --- |", "| 1 |"].join("\n")
+        );
+    }
+
+    let html = [
+        "<table>",
+        "<caption>Cities &amp; towns</caption>",
+        "<thead>",
+        r#"<tr><th rowspan="2">City</th><th colspan="2">Population</th></tr>"#,
+        "<tr><th>2010</th><th>2020</th></tr>",
+        "</thead>",
+        "<tbody>",
+        r#"<tr><td><a href="https://example.org/wiki/Hu%E1%BA%BF">Huế</a></td><td><b>340,000</b></td><td>652_572</td></tr>"#,
+        r#"<tr><td>Đà Lạt | Lâm Đồng</td><td colspan="2">n/a</td></tr>"#,
+        "</tbody>",
+        "</table>",
+    ]
+    .join("\n");
+    assert_eq!(table.to_html(), html);
+    // merged cells of the spanned table are collapsed back
+    assert_eq!(spanned_table.to_html(), html);
+
+    // special characters in the text and attributes of the cells are escaped, and only meaningful attributes are kept
+    let extractor = strict_extractor();
+    let doc = Document::new(
+        "https://example.org/".to_owned(),
+        r#"<table><tr><td><a href="/?a=1&amp;b=2" class="ext" title="AT&amp;T">AT&amp;T &lt;script&gt;</a>, <abbr title='say "hi"' style="color: red">hi</abbr></td></tr></table>"#.to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, false, false, false)?;
+    assert_eq!(
+        tables[0].to_html(),
+        [
+            "<table>",
+            "<tbody>",
+            r#"<tr><td><a href="https://example.org/?a=1&amp;b=2">AT&amp;T &lt;script&gt;</a>, <abbr title="say &quot;hi&quot;">hi</abbr></td></tr>"#,
+            "</tbody>",
+            "</table>",
+        ]
+        .join("\n")
+    );
+
+    // `|` in links does not end the cell of the Markdown table
+    let doc = Document::new(
+        "https://example.org/".to_owned(),
+        r#"<table><tr><th>Link</th></tr><tr><td><a href="/search?q=a|b">a or b</a></td></tr></table>"#
+            .to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, false, false, false)?;
+    assert_eq!(
+        tables[0].to_markdown(None),
+        [
+            "| Link |",
+            "| --- |",
+            "| [a or b](https://example.org/search?q=a%7Cb) |",
+        ]
+        .join("\n")
+    );
+
+    // `|` and line breaks in code spans do not end the cell or the row of the Markdown table
+    let doc = Document::new(
+        "https://example.org/".to_owned(),
+        "<table><tr><th>Code</th></tr><tr><td><code>a | b\nc</code><br>d</td></tr></table>"
+            .to_owned(),
+    );
+    let tables = extractor.extract_tables(&doc, false, false, false)?;
+    assert_eq!(
+        tables[0].to_markdown(None),
+        ["| Code |", "| --- |", "| `a \\| b c`<br>d |"].join("\n")
+    );
+
+    assert_eq!(
+        table.to_latex(None),
+        [
+            r"\begin{table}",
+            r"\caption{Cities \& towns}",
+            r"\begin{tabular}{lll}",
+            r"\hline",
+            r"\multirow{2}{*}{City} & \multicolumn{2}{l}{Population} \\",
+            r" & 2010 & 2020 \\",
+            r"\hline",
+            r"\href{https://example.org/wiki/Hu\%E1\%BA\%BF}{Huế} & \textbf{340,000} & 652\_572 \\",
+            r"Đà Lạt | Lâm Đồng & \multicolumn{2}{l}{n/a} \\",
+            r"\hline",
+            r"\end{tabular}",
+            r"\end{table}",
+        ]
+        .join("\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_csv() -> Result<()> {
+    let tables = get_tables("table_render.html")?;
+    let table = &tables[0];
+
+    assert_eq!(
+        table.to_csv(&CSVOptions::default())?,
+        [
+            "City,Population / 2010,Population / 2020",
+            "Huế,\"340,000\",652_572",
+            "Đà Lạt | Lâm Đồng,n/a,n/a",
+            "",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        table.to_csv(&CSVOptions {
+            delimiter: b'\t',
+            quoting: CSVQuoting::NonNumeric,
+            link_columns: true,
+            ..CSVOptions::default()
+        })?,
+        [
+            "\"City\"\t\"City (links)\"\t\"Population / 2010\"\t\"Population / 2020\"",
+            "\"Huế\"\t\"https://example.org/wiki/Hu%E1%BA%BF\"\t\"340,000\"\t\"652_572\"",
+            "\"Đà Lạt | Lâm Đồng\"\t\"\"\t\"n/a\"\t\"n/a\"",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // without merging header rows, the table is read back as the spanned table
+    let options = CSVOptions {
+        header: false,
+        ..CSVOptions::default()
+    };
+    let mut read_options = options.clone();
+    read_options.header = true;
+    read_options.n_header_rows = Some(2);
+    let read_table = Table::from_csv(
+        table.id.clone(),
+        table.url.clone(),
+        &table.to_csv(&options)?,
+        &read_options,
+    )?;
+    assert_eq!(read_table.to_list(), table.span()?.to_list());
+    assert_eq!(read_table.detect_header(), (2, 0));
+    assert_eq!(
+        read_table
+            .rows
+            .iter()
+            .map(|r| r.section.as_str())
+            .collect::<Vec<_>>(),
+        vec!["thead", "thead", "tbody", "tbody"]
+    );
+
+    // tables without header rows are written with a row of empty column names, which is skipped when reading
+    let headerless = Table::from_csv(
+        String::new(),
+        String::new(),
+        "A,B\nC,D\n",
+        &CSVOptions {
+            header: false,
+            ..CSVOptions::default()
+        },
+    )?;
+    assert_eq!(headerless.detect_header(), (0, 0));
+    assert_eq!(headerless.to_csv(&CSVOptions::default())?, ",\nA,B\nC,D\n");
+    assert_eq!(
+        headerless.to_csv(&CSVOptions {
+            n_header_rows: Some(0),
+            ..CSVOptions::default()
+        })?,
+        ",\nA,B\nC,D\n"
+    );
+    let options = CSVOptions::default();
+    let read_table = Table::from_csv(
+        String::new(),
+        String::new(),
+        &headerless.to_csv(&options)?,
+        &options,
+    )?;
+    assert_eq!(read_table.to_list(), headerless.to_list());
+    assert_eq!(read_table.detect_header(), (0, 0));
+
+    Ok(())
+}
+
+#[test]
+fn test_from_json_baseline() -> Result<()> {
+    // a table serialized before rows and cells recorded their section, span and location
+    let dat = fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/table_baseline.json"),
+    )?;
+    let table = Table::from_json(&dat)?;
+
+    assert_eq!(
+        table.to_list(),
+        vec![vec!["Country", "Capital"], vec!["France", "Paris"]]
+    );
+    assert_eq!(table.location, None);
+    for row in &table.rows {
+        assert_eq!(row.section, "tbody");
+        assert_eq!(row.row_group, 0);
+        assert_eq!(row.location, None);
+        for cell in &row.cells {
+            assert!(cell.nested_table_ids.is_empty());
+            assert!(!cell.span_conflict);
+            assert_eq!(cell.origin, None);
+            assert_eq!(cell.location, None);
+        }
+    }
+
+    let table2 = Table::from_json(&table.to_json()?)?;
+    assert_eq!(table2.to_list(), table.to_list());
+    assert_eq!(
+        table2.rows[0].cells[0].value.to_html(true, false),
+        "<th>Country</th>"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_from_bytes_baseline() -> Result<()> {
+    // the table of `table_baseline.json` in the binary format written before the format had a version
+    let dat = fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/table_baseline.b64"),
+    )?;
+    let table = Table::from_base64(dat.trim())?;
+    assert_eq!(table.id, "table-0");
+    assert_eq!(table.caption, "Countries");
+    assert_eq!(
+        table.to_list(),
+        vec![vec!["Country", "Capital"], vec!["France", "Paris"]]
+    );
+    assert!(table.rows[0].cells.iter().all(|cell| cell.is_header));
+    assert!(table.rows.iter().all(|row| row.section == "tbody"));
+
+    // tables are written with the version and read back
+    let bytes = table.to_bytes()?;
+    assert!(bytes.starts_with(BINARY_MAGIC));
+    assert_eq!(bytes[BINARY_MAGIC.len()], BINARY_VERSION);
+    assert_eq!(Table::from_bytes(&bytes)?.to_list(), table.to_list());
+
+    // newer versions and other data are reported instead of decoded to wrong values
+    let mut newer = bytes.clone();
+    newer[BINARY_MAGIC.len()] = BINARY_VERSION + 1;
+    assert!(Table::from_bytes(&newer)
+        .unwrap_err()
+        .to_string()
+        .contains("newer version"));
+    assert!(Table::from_bytes(b"not a table").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rows_without_row_group() -> Result<()> {
+    let mut doc = Document::new(
+        "https://example.org/page".to_owned(),
+        "<table><thead><tr><th>City</th></tr></thead><tr><td>A</td></tr><tr><td>B</td></tr><tfoot><tr><td>Total</td></tr></tfoot></table>"
+            .to_owned(),
+    );
+    // the parser wraps rows that are direct children of the table in a tbody, unwrap them so that
+    // the rows are bare as in documents built without the parser
+    let tbody_id = doc
+        .html
+        .select(&Selector::parse("tbody").unwrap())
+        .next()
+        .unwrap()
+        .id();
+    let row_ids = doc
+        .html
+        .tree
+        .get(tbody_id)
+        .unwrap()
+        .children()
+        .map(|row| row.id())
+        .collect::<Vec<_>>();
+    let mut tbody = doc.html.tree.get_mut(tbody_id).unwrap();
+    for row_id in row_ids {
+        tbody.insert_id_before(row_id);
+    }
+    tbody.detach();
+    assert_eq!(
+        doc.html.root_element().html(),
+        "<html><head></head><body><table><thead><tr><th>City</th></tr></thead><tr><td>A</td></tr><tr><td>B</td></tr><tfoot><tr><td>Total</td></tr></tfoot></table></body></html>"
+    );
+
+    let extractor = strict_extractor();
+    let tables = extractor.extract_tables(&doc, false, false, false)?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(
+        tables[0].to_list(),
+        vec![vec!["City"], vec!["A"], vec!["B"], vec!["Total"]]
+    );
+    assert_eq!(
+        tables[0]
+            .rows
+            .iter()
+            .map(|r| (r.section.as_str(), r.row_group))
+            .collect::<Vec<_>>(),
+        vec![("thead", 0), ("tbody", 1), ("tbody", 1), ("tfoot", 2)]
+    );
+
     Ok(())
 }